@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use crate::instructions::create_invoice::PaymentError;
+
+/// Size of the Ed25519Program instruction header (count + padding)
+const HEADER_LEN: usize = 2;
+/// Size of one Ed25519SignatureOffsets entry
+const OFFSETS_LEN: usize = 14;
+/// Instruction index value meaning "data lives in this instruction"
+const CURRENT_IX: u16 = u16::MAX;
+
+/// Signature, signer and message carried by an Ed25519Program instruction
+pub struct Ed25519Signature {
+    pub pubkey: Pubkey,
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+/// Load the Ed25519Program instruction placed immediately before the current one
+/// and extract its single signature. The runtime has already verified it, so
+/// callers only need to check the signer and message they expect.
+pub fn load_previous_ed25519(instructions_sysvar: &AccountInfo) -> Result<Ed25519Signature> {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    require!(current > 0, PaymentError::InvalidSignature);
+
+    let ix = load_instruction_at_checked((current - 1) as usize, instructions_sysvar)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, PaymentError::InvalidSignature);
+    require!(ix.accounts.is_empty(), PaymentError::InvalidSignature);
+
+    parse_ed25519_data(&ix.data)
+}
+
+fn parse_ed25519_data(data: &[u8]) -> Result<Ed25519Signature> {
+    require!(data.len() >= HEADER_LEN + OFFSETS_LEN, PaymentError::InvalidSignature);
+    require!(data[0] == 1, PaymentError::InvalidSignature);
+
+    let offsets = &data[HEADER_LEN..HEADER_LEN + OFFSETS_LEN];
+    let read_u16 = |i: usize| u16::from_le_bytes([offsets[i], offsets[i + 1]]);
+
+    let signature_offset = read_u16(0) as usize;
+    let signature_ix = read_u16(2);
+    let pubkey_offset = read_u16(4) as usize;
+    let pubkey_ix = read_u16(6);
+    let message_offset = read_u16(8) as usize;
+    let message_size = read_u16(10) as usize;
+    let message_ix = read_u16(12);
+
+    // All data must be inline, otherwise the verified bytes could live elsewhere
+    require!(
+        signature_ix == CURRENT_IX && pubkey_ix == CURRENT_IX && message_ix == CURRENT_IX,
+        PaymentError::InvalidSignature
+    );
+
+    let signature = slice(data, signature_offset, 64)?;
+    let pubkey = slice(data, pubkey_offset, 32)?;
+    let message = slice(data, message_offset, message_size)?;
+
+    Ok(Ed25519Signature {
+        pubkey: Pubkey::try_from(pubkey).map_err(|_| error!(PaymentError::InvalidSignature))?,
+        signature: signature.try_into().map_err(|_| error!(PaymentError::InvalidSignature))?,
+        message: message.to_vec(),
+    })
+}
+
+fn slice(data: &[u8], offset: usize, len: usize) -> Result<&[u8]> {
+    let end = offset.checked_add(len).ok_or(PaymentError::InvalidSignature)?;
+    data.get(offset..end).ok_or_else(|| error!(PaymentError::InvalidSignature))
+}
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TransferChecked};
+use crate::instructions::PaymentError;
+
+/// Transfer fees withheld on a Token-2022 token account (0 for any other account)
+pub fn withheld_fees(account: &AccountInfo) -> Result<u64> {
+    if account.owner != &spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let data = account.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map_or(0, |fees| u64::from(fees.withheld_amount)))
+}
+
+/// Transfer `amount` into `to` and return how much actually arrived.
+///
+/// Token-2022 mints may withhold a transfer fee from the destination, so the
+/// credited amount is measured from the account balance rather than trusted
+/// from the instruction argument.
+pub fn transfer_received<'info>(
+    from: AccountInfo<'info>,
+    to: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = to.amount;
+
+    let cpi_accounts = TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+    };
+    let cpi_ctx = CpiContext::new(token_program, cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+    to.reload()?;
+    Ok(to.amount
+        .checked_sub(balance_before)
+        .ok_or(PaymentError::MathOverflow)?)
+}
+
+/// Return `amount` from a payment escrow to the payer's token account
+pub fn refund_escrow_to_payer<'info>(
+    escrow: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer_token_account: AccountInfo<'info>,
+    escrow_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: escrow,
+        mint: mint.to_account_info(),
+        to: payer_token_account,
+        authority: escrow_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Pay each `(destination, share)` out of an escrow held by the escrow authority PDA,
+/// skipping zero shares
+pub fn pay_out_shares<'info>(
+    escrow: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    escrow_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    shares: impl IntoIterator<Item = (AccountInfo<'info>, u64)>,
+) -> Result<()> {
+    for (to, share) in shares {
+        if share == 0 {
+            continue;
+        }
+
+        let cpi_accounts = TransferChecked {
+            from: escrow.clone(),
+            mint: mint.to_account_info(),
+            to,
+            authority: escrow_authority.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds);
+
+        token_interface::transfer_checked(cpi_ctx, share, mint.decimals)?;
+    }
+
+    Ok(())
+}
+
+/// Close an emptied escrow token account owned by the escrow authority PDA.
+///
+/// Token-2022 refuses to close an account still holding withheld transfer fees,
+/// so those are harvested to the mint first. Harvesting is permissionless.
+pub fn close_escrow<'info>(
+    escrow: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    escrow_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if withheld_fees(&escrow)? > 0 {
+        let cpi_accounts = HarvestWithheldTokensToMint {
+            token_program_id: token_program.clone(),
+            mint,
+        };
+        let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts);
+
+        harvest_withheld_tokens_to_mint(cpi_ctx, vec![escrow.clone()])?;
+    }
+
+    let cpi_accounts = CloseAccount {
+        account: escrow,
+        destination,
+        authority: escrow_authority,
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+
+    token_interface::close_account(cpi_ctx)
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = platform_config.pending_admin == Some(new_admin.key()) @ PaymentError::NotPendingAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    let previous_admin = platform_config.admin;
+
+    platform_config.admin = ctx.accounts.new_admin.key();
+    platform_config.pending_admin = None;
+
+    msg!("Admin handover accepted: {} -> {}", previous_admin, platform_config.admin);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{PaymentState, state::Payment};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct AcceptResult<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = payment.payer == payer.key() @ PaymentError::Unauthorized,
+        constraint = payment.state == PaymentState::Completed || payment.state == PaymentState::ReceiptMinted @ PaymentError::InvalidState,
+        constraint = !payment.result_accepted @ PaymentError::ResultAlreadyAccepted
+    )]
+    pub payment: Account<'info, Payment>,
+}
+
+pub fn handler(ctx: Context<AcceptResult>) -> Result<()> {
+    let payment = &mut ctx.accounts.payment;
+    let clock = Clock::get()?;
+
+    // The payer gives up the right to dispute, so the recipient may claim now
+    payment.result_accepted = true;
+    payment.claimable_at = payment.claimable_at.min(clock.unix_timestamp);
+
+    emit!(ResultAccepted {
+        payment_id: payment.payment_id,
+        payer: payment.payer,
+        accepted_at: clock.unix_timestamp,
+    });
+
+    msg!("Result accepted: {}", payment.payment_id);
+    Ok(())
+}
+
+#[event]
+pub struct ResultAccepted {
+    pub payment_id: Pubkey,
+    pub payer: Pubkey,
+    pub accepted_at: i64,
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{AcceptedMint, PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct AddAcceptedMint<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Platform authority PDA - owns the fee treasury
+    /// CHECK: PDA authority
+    #[account(
+        seeds = [b"platform_authority"],
+        bump,
+    )]
+    pub platform_authority: UncheckedAccount<'info>,
+
+    /// Mint to accept
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AcceptedMint::LEN,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump,
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Fee treasury token account for this mint
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = platform_authority,
+        token::token_program = token_program,
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddAcceptedMint>) -> Result<()> {
+    let clock = Clock::get()?;
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+
+    accepted_mint.mint = ctx.accounts.mint.key();
+    accepted_mint.fee_treasury = ctx.accounts.fee_treasury.key();
+    accepted_mint.is_active = true;
+    accepted_mint.referral_liability = 0;
+    accepted_mint.added_at = clock.unix_timestamp;
+    accepted_mint.bump = ctx.bumps.accepted_mint;
+
+    msg!("Accepted mint added: {}", accepted_mint.mint);
+    msg!("Fee Treasury: {}", accepted_mint.fee_treasury);
+    Ok(())
+}
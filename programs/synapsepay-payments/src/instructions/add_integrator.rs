@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{Integrator, PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddIntegrator<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Integrator::LEN,
+        seeds = [b"integrator", wallet.as_ref()],
+        bump,
+    )]
+    pub integrator: Account<'info, Integrator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddIntegrator>, wallet: Pubkey) -> Result<()> {
+    let integrator = &mut ctx.accounts.integrator;
+
+    integrator.wallet = wallet;
+    integrator.is_active = true;
+    integrator.added_at = Clock::get()?.unix_timestamp;
+    integrator.bump = ctx.bumps.integrator;
+
+    msg!("Integrator added: {}", wallet);
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentMode, PaymentState, escrow::pay_out_shares, state::{AcceptedMint, Invoice, Payment, PlatformConfig, ReferralAccrual}};
+use super::complete_task::record_completion;
+use super::verify_payment::credit_referral;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CapturePayment<'info> {
+    /// Registered facilitator, funds the referrer's accrual if it is new
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.is_facilitator(&authority.key()) @ PaymentError::NotFacilitator
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState,
+        constraint = payment.mode == PaymentMode::Hold @ PaymentError::InvalidState
+    )]
+    pub payment: Account<'info, Payment>,
+
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Referrer's accrual for `mint`, required when the invoice has a referrer
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = ReferralAccrual::LEN,
+        seeds = [b"referral", invoice.referrer.unwrap_or_default().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub referral_accrual: Option<Account<'info, ReferralAccrual>>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Platform fee treasury for `mint`
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account, receives the uncaptured remainder
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CapturePayment>, result_cid: String, capture_amount: u64) -> Result<()> {
+    let escrow_authority_bump = ctx.bumps.escrow_authority;
+    let referral_bump = ctx.bumps.referral_accrual;
+    ctx.accounts.capture(escrow_authority_bump, referral_bump, result_cid, capture_amount)
+}
+
+impl<'info> CapturePayment<'info> {
+    /// Charge `capture_amount` of the hold, release the rest to the payer and
+    /// mark the payment completed
+    pub(crate) fn capture(
+        &mut self,
+        escrow_authority_bump: u8,
+        referral_bump: Option<u8>,
+        result_cid: String,
+        capture_amount: u64,
+    ) -> Result<()> {
+        let hold = self.payment.amount;
+        require!(capture_amount <= hold, PaymentError::CaptureExceedsHold);
+
+        // The fee is charged on what was captured, not on the hold, at the settled rate
+        let platform_fee = self.platform_config.compute_fee_at(capture_amount, self.payment.fee_bps)?;
+        let remainder = hold - capture_amount;
+
+        let seeds = &[
+            b"escrow_authority".as_ref(),
+            &[escrow_authority_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        let treasury_before = self.fee_treasury.amount;
+
+        pay_out_shares(
+            self.escrow_account.to_account_info(),
+            &self.mint,
+            self.escrow_authority.to_account_info(),
+            self.token_program.to_account_info(),
+            signer_seeds,
+            [
+                (self.fee_treasury.to_account_info(), platform_fee),
+                (self.payer_token_account.to_account_info(), remainder),
+            ],
+        )?;
+
+        // Whatever is left in escrow is what the recipient can claim
+        self.escrow_account.reload()?;
+        self.fee_treasury.reload()?;
+
+        let platform_fee = self.fee_treasury.amount
+            .checked_sub(treasury_before)
+            .ok_or(PaymentError::MathOverflow)?;
+
+        // Holds pay their fee here, so this is where the referrer earns its share
+        let referral_fee = credit_referral(
+            self.referral_accrual.as_mut(),
+            referral_bump,
+            &mut self.accepted_mint,
+            &self.platform_config,
+            &self.invoice,
+            platform_fee,
+        )?;
+
+        let payment = &mut self.payment;
+        payment.captured_amount = capture_amount;
+        payment.amount = self.escrow_account.amount;
+        payment.platform_fee = platform_fee;
+        payment.referral_fee = referral_fee;
+
+        msg!(
+            "Hold captured: {} of {} ({} fee, {} released to payer)",
+            capture_amount,
+            hold,
+            payment.platform_fee,
+            remainder
+        );
+
+        record_completion(payment, &self.invoice, &self.platform_config, result_cid)
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use crate::ed25519::load_previous_ed25519;
+use super::capture_payment::*;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CaptureUsage<'info> {
+    /// Hold capture accounts
+    pub capture: CapturePayment<'info>,
+
+    /// Instructions sysvar for Ed25519 signature introspection
+    /// CHECK: Address is checked against the sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CaptureUsage>, result_cid: String, units: u64) -> Result<()> {
+    let invoice = &ctx.accounts.capture.invoice;
+    let payment = &ctx.accounts.capture.payment;
+
+    require!(
+        invoice.unit_price > 0 && invoice.executor != Pubkey::default(),
+        PaymentError::MeteringNotEnabled
+    );
+
+    // The usage report must be signed by the executor the agent registered
+    let report = load_previous_ed25519(&ctx.accounts.instructions_sysvar.to_account_info())?;
+    require_keys_eq!(report.pubkey, invoice.executor, PaymentError::InvalidSignature);
+    require!(
+        report.message == payment.usage_report_message(units, &result_cid),
+        PaymentError::InvalidSignature
+    );
+
+    // Charge units x unit price, capped by what the payer authorized
+    let charge = units.saturating_mul(invoice.unit_price).min(payment.amount);
+
+    msg!("Usage reported: {} units at {} = {}", units, invoice.unit_price, charge);
+
+    let escrow_authority_bump = ctx.bumps.capture.escrow_authority;
+    let referral_bump = ctx.bumps.capture.referral_accrual;
+    ctx.accounts.capture.capture(escrow_authority_bump, referral_bump, result_cid, charge)
+}
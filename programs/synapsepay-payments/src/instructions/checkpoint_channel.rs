@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{ed25519::load_previous_ed25519, escrow::pay_out_shares, state::{AcceptedMint, Channel, PlatformConfig}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CheckpointChannel<'info> {
+    /// Agent owner
+    pub recipient: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = channel.recipient == recipient.key() @ PaymentError::Unauthorized
+    )]
+    pub channel: Account<'info, Channel>,
+
+    /// Payment token mint, receives any withheld transfer fees on close
+    #[account(
+        mut,
+        address = channel.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Channel escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"channel_escrow", channel.key().as_ref()],
+        bump,
+        constraint = channel_escrow.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub channel_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == channel.mint @ PaymentError::InvalidMint,
+        constraint = recipient_token_account.owner == channel.recipient @ PaymentError::InvalidTokenOwner
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Platform fee treasury for `mint`
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// Instructions sysvar for Ed25519 signature introspection
+    /// CHECK: Address is checked against the sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<CheckpointChannel>, cumulative_amount: u64) -> Result<()> {
+    require!(
+        cumulative_amount > ctx.accounts.channel.redeemed,
+        PaymentError::VoucherNotIncreasing
+    );
+
+    let escrow_authority_bump = ctx.bumps.escrow_authority;
+    ctx.accounts.redeem(escrow_authority_bump, cumulative_amount)
+}
+
+impl<'info> CheckpointChannel<'info> {
+    /// Pay out the part of a payer-signed cumulative voucher not yet redeemed.
+    /// A voucher equal to what was already redeemed is a no-op and needs no signature.
+    pub(crate) fn redeem(&mut self, escrow_authority_bump: u8, cumulative_amount: u64) -> Result<()> {
+        let channel = &self.channel;
+        require!(cumulative_amount >= channel.redeemed, PaymentError::VoucherNotIncreasing);
+        require!(cumulative_amount <= channel.deposit, PaymentError::VoucherExceedsDeposit);
+        if cumulative_amount == channel.redeemed {
+            return Ok(());
+        }
+
+        // The voucher must be signed by the payer for this channel
+        let voucher = load_previous_ed25519(&self.instructions_sysvar.to_account_info())?;
+        require_keys_eq!(voucher.pubkey, channel.payer, PaymentError::InvalidSignature);
+        require!(
+            voucher.message == channel.voucher_message(cumulative_amount),
+            PaymentError::InvalidSignature
+        );
+
+        let delta = cumulative_amount - channel.redeemed;
+        // Fees follow the rate in force when the channel was opened
+        let platform_fee = PlatformConfig::fee_with(delta, channel.fee_bps, channel.min_fee)?;
+
+        let seeds = &[
+            b"escrow_authority".as_ref(),
+            &[escrow_authority_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        pay_out_shares(
+            self.channel_escrow.to_account_info(),
+            &self.mint,
+            self.escrow_authority.to_account_info(),
+            self.token_program.to_account_info(),
+            signer_seeds,
+            [
+                (self.fee_treasury.to_account_info(), platform_fee),
+                (self.recipient_token_account.to_account_info(), delta - platform_fee),
+            ],
+        )?;
+
+        let channel = &mut self.channel;
+        channel.redeemed = cumulative_amount;
+        channel.platform_fee = channel.platform_fee
+            .checked_add(platform_fee)
+            .ok_or(PaymentError::MathOverflow)?;
+
+        emit!(ChannelRedeemed {
+            channel_id: channel.channel_id,
+            cumulative_amount,
+            amount_paid: delta - platform_fee,
+            platform_fee,
+        });
+
+        msg!("Channel {} redeemed up to {} ({} fee)", channel.channel_id, cumulative_amount, platform_fee);
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ChannelRedeemed {
+    pub channel_id: Pubkey,
+    pub cumulative_amount: u64,
+    pub amount_paid: u64,
+    pub platform_fee: u64,
+}
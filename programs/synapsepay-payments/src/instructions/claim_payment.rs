@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{PaymentState, state::Payment};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use synapsepay_registry::Payee;
+use crate::{PaymentState, escrow::pay_out_shares, state::{Invoice, Payment, PlatformConfig}};
 use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
@@ -8,6 +9,12 @@ pub struct ClaimPayment<'info> {
     #[account(mut)]
     pub recipient: Signer<'info>,
 
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
     #[account(
         mut,
         constraint = payment.recipient == recipient.key() @ PaymentError::Unauthorized,
@@ -15,17 +22,35 @@ pub struct ClaimPayment<'info> {
     )]
     pub payment: Account<'info, Payment>,
 
+    /// Related invoice, carries the payee shares snapshotted at creation
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
     /// Payment escrow account (PDA)
     #[account(
         mut,
         seeds = [b"escrow", payment.key().as_ref()],
         bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
     )]
-    pub escrow_account: Account<'info, TokenAccount>,
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// Recipient's USDC token account
-    #[account(mut)]
-    pub recipient_token_account: Account<'info, TokenAccount>,
+    /// Recipient's token account
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = recipient_token_account.owner == payment.recipient @ PaymentError::InvalidTokenOwner
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Escrow authority PDA
     /// CHECK: PDA signer for escrow
@@ -35,34 +60,94 @@ pub struct ClaimPayment<'info> {
     )]
     pub escrow_authority: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn handler(ctx: Context<ClaimPayment>) -> Result<()> {
+/// Remaining accounts: one token account per invoice payee, in the same order
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimPayment<'info>>) -> Result<()> {
     let payment = &mut ctx.accounts.payment;
+    let payees = &ctx.accounts.invoice.payees;
     let amount = payment.amount;
 
-    // Transfer USDC from escrow to recipient
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= payment.claimable_at, PaymentError::ChallengePeriodActive);
+
+    // While the payer may still open a dispute, only an accepted result can be claimed
+    if !payment.result_accepted {
+        let window_ends_at = payment
+            .completed_at
+            .checked_add(ctx.accounts.platform_config.dispute_window)
+            .ok_or(PaymentError::MathOverflow)?;
+        require!(now > window_ends_at, PaymentError::ChallengePeriodActive);
+    }
+
+    let transfers = payee_transfers(
+        payees,
+        ctx.remaining_accounts,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        payment.mint,
+        amount,
+    )?;
+    let recipient_amount = transfers.last().map_or(0, |(_, share)| *share);
+
+    // Transfer USDC from escrow to payees and recipient
     let seeds = &[
         b"escrow_authority".as_ref(),
         &[ctx.bumps.escrow_authority],
     ];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.escrow_account.to_account_info(),
-        to: ctx.accounts.recipient_token_account.to_account_info(),
-        authority: ctx.accounts.escrow_authority.to_account_info(),
-    };
-
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-    token::transfer(cpi_ctx, amount)?;
+    pay_out_shares(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        transfers,
+    )?;
 
     // Update payment state
     payment.state = PaymentState::Claimed;
 
-    msg!("Payment claimed: {} - {} USDC transferred to recipient", payment.payment_id, amount);
+    msg!(
+        "Payment claimed: {} - {} USDC transferred to recipient, {} to {} payees",
+        payment.payment_id,
+        recipient_amount,
+        amount - recipient_amount,
+        payees.len()
+    );
     Ok(())
 }
+
+/// Split `amount` between the invoice payees and the recipient, returning one
+/// transfer per payee followed by the recipient's. Each payee gets its rounded-down
+/// share; the owner keeps the rest, dust included.
+///
+/// `payee_accounts` are the payees' token accounts, in invoice order.
+pub(crate) fn payee_transfers<'info>(
+    payees: &[Payee],
+    payee_accounts: &'info [AccountInfo<'info>],
+    recipient_token_account: AccountInfo<'info>,
+    mint: Pubkey,
+    amount: u64,
+) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+    require!(
+        payee_accounts.len() == payees.len(),
+        PaymentError::PayeeAccountsMismatch
+    );
+
+    let mut transfers = Vec::with_capacity(payees.len() + 1);
+    let mut recipient_amount = amount;
+    for (payee, account) in payees.iter().zip(payee_accounts.iter()) {
+        let payee_account = InterfaceAccount::<TokenAccount>::try_from(account)?;
+        require_keys_eq!(payee_account.mint, mint, PaymentError::InvalidMint);
+        require_keys_eq!(payee_account.owner, payee.wallet, PaymentError::InvalidTokenOwner);
+
+        let share = payee.share_of(amount);
+        recipient_amount -= share;
+        transfers.push((account.clone(), share));
+    }
+    transfers.push((recipient_token_account, recipient_amount));
+
+    Ok(transfers)
+}
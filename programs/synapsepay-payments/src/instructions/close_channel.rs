@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use crate::escrow::close_escrow;
+use super::checkpoint_channel::*;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CloseChannel<'info> {
+    /// Voucher redemption accounts
+    pub checkpoint: CheckpointChannel<'info>,
+
+    /// CHECK: Channel payer, receives the refund and reclaimed rent
+    #[account(
+        mut,
+        address = checkpoint.channel.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    /// Payer's token account, receives the unspent deposit
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == checkpoint.channel.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == checkpoint.channel.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handler(ctx: Context<CloseChannel>, cumulative_amount: u64) -> Result<()> {
+    let escrow_authority_bump = ctx.bumps.checkpoint.escrow_authority;
+    let checkpoint = &mut ctx.accounts.checkpoint;
+
+    // Redeem the final voucher, then hand everything else back to the payer
+    checkpoint.redeem(escrow_authority_bump, cumulative_amount)?;
+
+    let refunded = refund_and_close_escrow(
+        &mut checkpoint.channel_escrow,
+        &checkpoint.mint,
+        &ctx.accounts.payer_token_account,
+        ctx.accounts.payer.to_account_info(),
+        checkpoint.escrow_authority.to_account_info(),
+        checkpoint.token_program.to_account_info(),
+        escrow_authority_bump,
+    )?;
+
+    emit!(ChannelClosed {
+        channel_id: checkpoint.channel.channel_id,
+        redeemed: checkpoint.channel.redeemed,
+        refunded,
+        unilateral: false,
+    });
+
+    msg!("Channel closed: {} ({} refunded)", checkpoint.channel.channel_id, refunded);
+    checkpoint.channel.close(ctx.accounts.payer.to_account_info())
+}
+
+/// Refund whatever is left in a channel or stream escrow to the payer and close it
+pub(crate) fn refund_and_close_escrow<'info>(
+    escrow: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer_token_account: &InterfaceAccount<'info, TokenAccount>,
+    payer: AccountInfo<'info>,
+    escrow_authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    escrow_authority_bump: u8,
+) -> Result<u64> {
+    let seeds = &[
+        b"escrow_authority".as_ref(),
+        &[escrow_authority_bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // Redemptions earlier in this instruction may have moved funds out
+    escrow.reload()?;
+    let refund = escrow.amount;
+    if refund > 0 {
+        let cpi_accounts = TransferChecked {
+            from: escrow.to_account_info(),
+            mint: mint.to_account_info(),
+            to: payer_token_account.to_account_info(),
+            authority: escrow_authority.clone(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, refund, mint.decimals)?;
+    }
+
+    close_escrow(
+        escrow.to_account_info(),
+        mint.to_account_info(),
+        payer,
+        escrow_authority,
+        token_program,
+        signer_seeds,
+    )?;
+
+    Ok(refund)
+}
+
+#[event]
+pub struct ChannelClosed {
+    pub channel_id: Pubkey,
+    pub redeemed: u64,
+    pub refunded: u64,
+    pub unilateral: bool,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::Invoice;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CloseInvoice<'info> {
+    /// Anyone may close a finished invoice; rent always goes to the payer
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = invoice.state.is_terminal() @ PaymentError::InvalidState,
+        close = payer
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// CHECK: Original rent payer, receives the reclaimed lamports
+    #[account(
+        mut,
+        address = invoice.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CloseInvoice>) -> Result<()> {
+    msg!("Invoice closed: {}", ctx.accounts.invoice.invoice_id);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{escrow::close_escrow, state::{Invoice, Payment}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct ClosePayment<'info> {
+    /// Anyone may close a finished payment; rent always goes to the payer
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = payment.state.is_terminal() @ PaymentError::InvalidState,
+        close = payer
+    )]
+    pub payment: Account<'info, Payment>,
+
+    /// Related invoice, marked with the payment's final state
+    #[account(
+        mut,
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint, receives any withheld transfer fees
+    #[account(
+        mut,
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Original rent payer, receives the reclaimed lamports
+    #[account(
+        mut,
+        address = payment.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClosePayment>) -> Result<()> {
+    let payment = &ctx.accounts.payment;
+
+    // Close the emptied escrow token account
+    let seeds = &[
+        b"escrow_authority".as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    close_escrow(
+        ctx.accounts.escrow_account.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+    )?;
+
+    // Let the invoice be closed once the payment is gone
+    ctx.accounts.invoice.state = payment.state.clone();
+
+    msg!("Payment closed: {}", payment.payment_id);
+    Ok(())
+}
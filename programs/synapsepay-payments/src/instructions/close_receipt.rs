@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::state::Receipt;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct CloseReceipt<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = payer @ PaymentError::Unauthorized,
+        close = payer
+    )]
+    pub receipt: Account<'info, Receipt>,
+}
+
+pub fn handler(ctx: Context<CloseReceipt>) -> Result<()> {
+    msg!("Receipt closed: {}", ctx.accounts.receipt.receipt_id);
+    Ok(())
+}
@@ -1,26 +1,70 @@
 use anchor_lang::prelude::*;
-use crate::{PaymentState, state::Payment};
+use crate::{PaymentMode, PaymentState, state::{Invoice, Payment, PlatformConfig}};
 use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
 pub struct CompleteTask<'info> {
-    /// Platform authority or facilitator
+    /// Registered facilitator
     pub authority: Signer<'info>,
 
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.is_facilitator(&authority.key()) @ PaymentError::NotFacilitator
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
     #[account(
         mut,
-        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState
+        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState,
+        constraint = payment.mode == PaymentMode::Fixed @ PaymentError::CaptureRequired
     )]
     pub payment: Account<'info, Payment>,
+
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
 }
 
 pub fn handler(ctx: Context<CompleteTask>, result_cid: String) -> Result<()> {
-    let payment = &mut ctx.accounts.payment;
+    record_completion(
+        &mut ctx.accounts.payment,
+        &ctx.accounts.invoice,
+        &ctx.accounts.platform_config,
+        result_cid,
+    )
+}
+
+/// Mark a payment completed, start its challenge window and emit `TaskCompleted`
+pub(crate) fn record_completion(
+    payment: &mut Payment,
+    invoice: &Invoice,
+    platform_config: &PlatformConfig,
+    result_cid: String,
+) -> Result<()> {
+    let clock = Clock::get()?;
 
-    require!(result_cid.len() <= Payment::MAX_RESULT_CID_LEN, PaymentError::AgentIdTooLong);
+    require!(result_cid.len() <= Payment::MAX_RESULT_CID_LEN, PaymentError::ResultCidTooLong);
+    require!(
+        clock.unix_timestamp <= invoice.execution_deadline,
+        PaymentError::DeadlinePassed
+    );
 
     payment.result_cid = result_cid.clone();
     payment.state = PaymentState::Completed;
+    payment.completed_at = clock.unix_timestamp;
+
+    // Per-agent challenge window, falling back to the platform default. It never
+    // ends before the dispute window, so a claim cannot front-run a dispute.
+    let challenge_window = match invoice.challenge_window {
+        0 => platform_config.challenge_window,
+        window => window,
+    };
+    payment.claimable_at = clock
+        .unix_timestamp
+        .checked_add(challenge_window.max(platform_config.dispute_window))
+        .ok_or(PaymentError::MathOverflow)?;
 
     // Emit event for off-chain indexing
     emit!(TaskCompleted {
@@ -29,7 +73,8 @@ pub fn handler(ctx: Context<CompleteTask>, result_cid: String) -> Result<()> {
         recipient: payment.recipient,
         amount: payment.amount,
         result_cid,
-        completed_at: Clock::get()?.unix_timestamp,
+        completed_at: clock.unix_timestamp,
+        claimable_at: payment.claimable_at,
     });
 
     msg!("Task completed: {} - result: {}", payment.payment_id, payment.result_cid);
@@ -44,5 +89,5 @@ pub struct TaskCompleted {
     pub amount: u64,
     pub result_cid: String,
     pub completed_at: i64,
+    pub claimable_at: i64,
 }
-
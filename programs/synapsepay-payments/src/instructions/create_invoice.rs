@@ -1,49 +1,133 @@
 use anchor_lang::prelude::*;
-use crate::{PaymentState, state::Invoice};
+use anchor_spl::token_interface::Mint;
+use synapsepay_registry::state::Agent;
+use crate::{PaymentMode, PaymentState, state::{AcceptedMint, Integrator, Invoice, NonceTracker}};
 
 #[derive(Accounts)]
-#[instruction(agent_id: String)]
+#[instruction(agent_id: String, nonce: u64, amount: u64, expires_at: i64, mode: PaymentMode, referrer: Option<Pubkey>)]
 pub struct CreateInvoice<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// CHECK: Agent owner's wallet
+    /// Registry agent being paid
+    #[account(
+        seeds = [b"agent", agent_id.as_bytes()],
+        bump = agent.bump,
+        seeds::program = synapsepay_registry::ID,
+        constraint = agent.is_active @ PaymentError::AgentNotActive
+    )]
+    pub agent: Account<'info, Agent>,
+
+    /// CHECK: Agent owner's wallet, checked against the registry
+    #[account(
+        constraint = recipient.key() == agent.owner @ PaymentError::RecipientMismatch
+    )]
     pub recipient: UncheckedAccount<'info>,
 
+    /// Payment token mint
+    #[account(
+        constraint = agent.accepts_mint(&mint.key()) @ PaymentError::AgentMintNotAccepted
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump,
+        constraint = accepted_mint.is_active @ PaymentError::MintNotAccepted
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Registration of `referrer`, required when the invoice names one
+    #[account(
+        seeds = [b"integrator", referrer.unwrap_or_default().as_ref()],
+        bump = integrator.bump
+    )]
+    pub integrator: Option<Account<'info, Integrator>>,
+
     #[account(
         init,
         payer = payer,
         space = Invoice::LEN,
-        seeds = [b"invoice", payer.key().as_ref(), agent_id.as_bytes(), &Clock::get()?.unix_timestamp.to_le_bytes()],
+        seeds = [b"invoice", payer.key().as_ref(), agent_id.as_bytes(), &nonce.to_le_bytes()],
         bump
     )]
     pub invoice: Account<'info, Invoice>,
 
+    /// Payer's nonce high-water mark
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = NonceTracker::LEN,
+        seeds = [b"nonce_tracker", payer.key().as_ref()],
+        bump
+    )]
+    pub nonce_tracker: Account<'info, NonceTracker>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<CreateInvoice>,
     agent_id: String,
+    nonce: u64,
     amount: u64,
     expires_at: i64,
+    mode: PaymentMode,
+    referrer: Option<Pubkey>,
 ) -> Result<()> {
     let invoice = &mut ctx.accounts.invoice;
+    let nonce_tracker = &mut ctx.accounts.nonce_tracker;
+    let agent = &ctx.accounts.agent;
     let clock = Clock::get()?;
 
     require!(amount > 0, PaymentError::InvalidAmount);
+    // The agent price is in USDC base units, so it only bounds amounts in a mint of the same precision
+    if ctx.accounts.mint.decimals == Agent::PRICE_DECIMALS {
+        require!(amount >= agent.price, PaymentError::AmountBelowAgentPrice);
+    }
     require!(expires_at > clock.unix_timestamp, PaymentError::InvalidExpiry);
     require!(agent_id.len() <= Invoice::MAX_AGENT_ID_LEN, PaymentError::AgentIdTooLong);
+    require!(nonce >= nonce_tracker.next_nonce, PaymentError::NonceAlreadyUsed);
+    // Parties to the payment cannot refer themselves into a fee rebate
+    require!(
+        !referrer.is_some_and(|r| r == ctx.accounts.payer.key() || r == ctx.accounts.recipient.key()),
+        PaymentError::InvalidReferrer
+    );
+    // Only wallets the platform registered as integrators earn referral fees
+    if referrer.is_some() {
+        require!(
+            ctx.accounts.integrator.as_ref().is_some_and(|i| i.is_active),
+            PaymentError::ReferrerNotRegistered
+        );
+    }
+
+    // Advance the payer's high-water mark so this nonce can never be reused
+    if nonce_tracker.payer == Pubkey::default() {
+        nonce_tracker.payer = ctx.accounts.payer.key();
+        nonce_tracker.bump = ctx.bumps.nonce_tracker;
+    }
+    nonce_tracker.next_nonce = nonce.checked_add(1).ok_or(PaymentError::MathOverflow)?;
 
     invoice.invoice_id = invoice.key();
     invoice.payer = ctx.accounts.payer.key();
     invoice.recipient = ctx.accounts.recipient.key();
+    invoice.agent = agent.key();
     invoice.agent_id = agent_id;
+    invoice.agent_price = agent.price;
+    invoice.challenge_window = agent.challenge_window;
+    invoice.sla_seconds = agent.sla_seconds;
+    invoice.unit_price = agent.unit_price;
+    invoice.executor = agent.executor;
+    invoice.payees = agent.payees.clone();
+    invoice.referrer = referrer;
+    invoice.mint = ctx.accounts.mint.key();
     invoice.amount = amount;
+    invoice.mode = mode;
     invoice.state = PaymentState::InvoiceCreated;
     invoice.expires_at = expires_at;
     invoice.created_at = clock.unix_timestamp;
-    invoice.nonce = clock.unix_timestamp as u64;
+    invoice.nonce = nonce;
     invoice.bump = ctx.bumps.invoice;
 
     msg!("Invoice created: {}", invoice.invoice_id);
@@ -68,4 +152,100 @@ pub enum PaymentError {
     InvalidSignature,
     #[msg("Nonce already used")]
     NonceAlreadyUsed,
+    #[msg("Fee basis points exceed 10000")]
+    InvalidFeeBps,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Signer is not the platform admin")]
+    NotAdmin,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Signer is not a registered facilitator")]
+    NotFacilitator,
+    #[msg("Signer is not a registered refund operator")]
+    NotRefundOperator,
+    #[msg("Role already granted")]
+    RoleAlreadyGranted,
+    #[msg("Role not granted")]
+    RoleNotGranted,
+    #[msg("Role member list is full")]
+    RoleListFull,
+    #[msg("Agent is not active")]
+    AgentNotActive,
+    #[msg("Recipient is not the agent owner")]
+    RecipientMismatch,
+    #[msg("Amount is below the agent price")]
+    AmountBelowAgentPrice,
+    #[msg("Invalid timeout")]
+    InvalidTimeout,
+    #[msg("Invoice has not expired yet")]
+    InvoiceNotExpired,
+    #[msg("Pending payment has not timed out yet")]
+    PaymentNotTimedOut,
+    #[msg("Token mint does not match the expected mint")]
+    InvalidMint,
+    #[msg("Token account is not owned by the expected wallet")]
+    InvalidTokenOwner,
+    #[msg("Escrow account is not owned by the escrow authority")]
+    InvalidEscrowAuthority,
+    #[msg("Fee treasury does not match the accepted mint")]
+    InvalidFeeTreasury,
+    #[msg("Mint is not an active accepted mint")]
+    MintNotAccepted,
+    #[msg("Agent does not accept this mint")]
+    AgentMintNotAccepted,
+    #[msg("Signer is not a registered arbiter")]
+    NotArbiter,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Share basis points exceed 10000")]
+    InvalidShareBps,
+    #[msg("Challenge period has not ended yet")]
+    ChallengePeriodActive,
+    #[msg("Result was already accepted")]
+    ResultAlreadyAccepted,
+    #[msg("Execution deadline has passed")]
+    DeadlinePassed,
+    #[msg("Execution deadline has not passed yet")]
+    DeadlineNotReached,
+    #[msg("Result CID too long")]
+    ResultCidTooLong,
+    #[msg("Split amount exceeds the escrowed amount")]
+    InvalidSplitAmount,
+    #[msg("Split needs the other party or a facilitator to co-sign")]
+    SplitNotApproved,
+    #[msg("Hold payments must be completed with capture_payment")]
+    CaptureRequired,
+    #[msg("Capture amount exceeds the authorized hold")]
+    CaptureExceedsHold,
+    #[msg("Agent does not use metered pricing")]
+    MeteringNotEnabled,
+    #[msg("Receipt tree is full")]
+    ReceiptTreeFull,
+    #[msg("Receipt proof does not match a recent root")]
+    InvalidReceiptProof,
+    #[msg("Channel close timeout is too short")]
+    InvalidChannelTimeout,
+    #[msg("Voucher does not exceed the amount already redeemed")]
+    VoucherNotIncreasing,
+    #[msg("Voucher exceeds the channel deposit")]
+    VoucherExceedsDeposit,
+    #[msg("Payer has not requested a channel close")]
+    ChannelCloseNotRequested,
+    #[msg("Channel close timeout has not elapsed")]
+    ChannelTimeoutNotElapsed,
+    #[msg("Remaining accounts do not match the invoice payees")]
+    PayeeAccountsMismatch,
+    #[msg("Payer or recipient cannot be the referrer")]
+    InvalidReferrer,
+    #[msg("Referral accrual account is required for a referred payment")]
+    ReferralAccountMissing,
+    #[msg("No referral fees to withdraw")]
+    NoReferralFees,
+    #[msg("Fee tiers must number at most 5 with strictly increasing thresholds and rates at or below the base fee")]
+    InvalidFeeTiers,
+    #[msg("Referrer is not an active registered integrator")]
+    ReferrerNotRegistered,
+    #[msg("Fee treasury holds too little unowed balance to refund the fee")]
+    InsufficientTreasury,
 }
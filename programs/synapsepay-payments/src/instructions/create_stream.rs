@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use synapsepay_registry::state::Agent;
+use crate::{escrow::transfer_received, state::{AcceptedMint, NonceTracker, PlatformConfig, Stream}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+#[instruction(agent_id: String, nonce: u64)]
+pub struct CreateStream<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Registry agent being paid
+    #[account(
+        seeds = [b"agent", agent_id.as_bytes()],
+        bump = agent.bump,
+        seeds::program = synapsepay_registry::ID,
+        constraint = agent.is_active @ PaymentError::AgentNotActive
+    )]
+    pub agent: Account<'info, Agent>,
+
+    /// CHECK: Agent owner's wallet, checked against the registry
+    #[account(
+        constraint = recipient.key() == agent.owner @ PaymentError::RecipientMismatch
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Payment token mint
+    #[account(
+        constraint = agent.accepts_mint(&mint.key()) @ PaymentError::AgentMintNotAccepted
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump,
+        constraint = accepted_mint.is_active @ PaymentError::MintNotAccepted
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Stream::LEN,
+        seeds = [b"stream", payer.key().as_ref(), agent.key().as_ref(), &nonce.to_le_bytes()],
+        bump
+    )]
+    pub stream: Account<'info, Stream>,
+
+    /// Payer's nonce high-water mark
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = NonceTracker::LEN,
+        seeds = [b"nonce_tracker", payer.key().as_ref()],
+        bump
+    )]
+    pub nonce_tracker: Account<'info, NonceTracker>,
+
+    /// Payer's token account
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == mint.key() @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payer.key() @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Stream escrow account (PDA)
+    #[account(
+        init,
+        payer = payer,
+        seeds = [b"stream_escrow", stream.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program,
+    )]
+    pub stream_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA owner of the escrow account
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateStream>,
+    _agent_id: String,
+    nonce: u64,
+    deposit: u64,
+    rate_per_second: u64,
+) -> Result<()> {
+    let nonce_tracker = &mut ctx.accounts.nonce_tracker;
+
+    require!(deposit > 0 && rate_per_second > 0, PaymentError::InvalidAmount);
+    require!(nonce >= nonce_tracker.next_nonce, PaymentError::NonceAlreadyUsed);
+
+    // Advance the payer's high-water mark so this stream address can never be reused
+    if nonce_tracker.payer == Pubkey::default() {
+        nonce_tracker.payer = ctx.accounts.payer.key();
+        nonce_tracker.bump = ctx.bumps.nonce_tracker;
+    }
+    nonce_tracker.next_nonce = nonce.checked_add(1).ok_or(PaymentError::MathOverflow)?;
+
+    // Transfer the deposit from payer to the stream escrow
+    let received = transfer_received(
+        ctx.accounts.payer_token_account.to_account_info(),
+        &mut ctx.accounts.stream_escrow,
+        &ctx.accounts.mint,
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        deposit,
+    )?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.stream_id = stream.key();
+    stream.payer = ctx.accounts.payer.key();
+    stream.recipient = ctx.accounts.recipient.key();
+    stream.agent = ctx.accounts.agent.key();
+    stream.mint = ctx.accounts.mint.key();
+    stream.nonce = nonce;
+    stream.deposit = received;
+    stream.rate_per_second = rate_per_second;
+    stream.withdrawn = 0;
+    stream.platform_fee = 0;
+    stream.fee_bps = ctx.accounts.platform_config.fee_bps;
+    stream.min_fee = ctx.accounts.platform_config.min_fee;
+    stream.start_time = Clock::get()?.unix_timestamp;
+    stream.bump = ctx.bumps.stream;
+
+    msg!(
+        "Stream created: {} at {}/s with deposit {}",
+        stream.stream_id,
+        stream.rate_per_second,
+        stream.deposit
+    );
+    Ok(())
+}
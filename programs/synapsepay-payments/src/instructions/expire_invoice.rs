@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::{PaymentState, state::Invoice};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct ExpireInvoice<'info> {
+    /// Anyone may expire a stale invoice
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = invoice.state == PaymentState::InvoiceCreated @ PaymentError::InvalidState,
+        close = payer
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// CHECK: Original rent payer, receives the reclaimed lamports
+    #[account(
+        mut,
+        address = invoice.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ExpireInvoice>) -> Result<()> {
+    let invoice = &mut ctx.accounts.invoice;
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp >= invoice.expires_at, PaymentError::InvoiceNotExpired);
+
+    invoice.state = PaymentState::Expired;
+
+    emit!(InvoiceExpired {
+        invoice_id: invoice.invoice_id,
+        payer: invoice.payer,
+        expired_at: clock.unix_timestamp,
+    });
+
+    msg!("Invoice expired: {}", invoice.invoice_id);
+    Ok(())
+}
+
+#[event]
+pub struct InvoiceExpired {
+    pub invoice_id: Pubkey,
+    pub payer: Pubkey,
+    pub expired_at: i64,
+}
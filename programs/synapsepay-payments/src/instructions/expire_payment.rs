@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::{PaymentState, state::{Invoice, Payment, PlatformConfig}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct ExpirePayment<'info> {
+    /// Anyone may expire an abandoned payment
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Pending @ PaymentError::InvalidState,
+        close = payer
+    )]
+    pub payment: Account<'info, Payment>,
+
+    #[account(
+        mut,
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState,
+        close = payer
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// CHECK: Original rent payer, receives the reclaimed lamports
+    #[account(
+        mut,
+        address = payment.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ExpirePayment>) -> Result<()> {
+    let payment = &mut ctx.accounts.payment;
+    let invoice = &mut ctx.accounts.invoice;
+    let clock = Clock::get()?;
+
+    let timeout_at = payment
+        .settled_at
+        .checked_add(ctx.accounts.platform_config.pending_timeout)
+        .ok_or(PaymentError::MathOverflow)?;
+    require!(clock.unix_timestamp >= timeout_at, PaymentError::PaymentNotTimedOut);
+
+    payment.state = PaymentState::Expired;
+    invoice.state = PaymentState::Expired;
+
+    emit!(PaymentExpired {
+        payment_id: payment.payment_id,
+        invoice_id: invoice.invoice_id,
+        payer: payment.payer,
+        expired_at: clock.unix_timestamp,
+    });
+
+    msg!("Pending payment expired: {}", payment.payment_id);
+    Ok(())
+}
+
+#[event]
+pub struct PaymentExpired {
+    pub payment_id: Pubkey,
+    pub invoice_id: Pubkey,
+    pub payer: Pubkey,
+    pub expired_at: i64,
+}
@@ -0,0 +1,213 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::{FailureReason, PaymentState, escrow::refund_escrow_to_payer, state::{AcceptedMint, Invoice, Payment, PlatformConfig, ReferralAccrual}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct FailTask<'info> {
+    /// Registered facilitator or the agent owner
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState,
+        constraint = platform_config.is_facilitator(&authority.key())
+            || payment.recipient == authority.key() @ PaymentError::Unauthorized
+    )]
+    pub payment: Account<'info, Payment>,
+
+    /// Related invoice, names the referrer credited at verification
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Referrer's accrual for `mint`, required when a credited fee is refunded
+    #[account(
+        mut,
+        seeds = [b"referral", invoice.referrer.unwrap_or_default().as_ref(), mint.key().as_ref()],
+        bump = referral_accrual.bump
+    )]
+    pub referral_accrual: Option<Account<'info, ReferralAccrual>>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Platform fee treasury for `mint`, debited when the fee is refunded
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account (original payer)
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    /// Platform authority PDA
+    /// CHECK: PDA signer for fee treasury
+    #[account(
+        seeds = [b"platform_authority"],
+        bump,
+    )]
+    pub platform_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(
+    ctx: Context<FailTask>,
+    reason: FailureReason,
+    error_cid: Option<String>,
+    refund_fee: bool,
+) -> Result<()> {
+    let error_cid = error_cid.unwrap_or_default();
+    require!(error_cid.len() <= Payment::MAX_RESULT_CID_LEN, PaymentError::ResultCidTooLong);
+
+    // Only the platform may give back its own fee
+    if refund_fee {
+        require!(
+            ctx.accounts.platform_config.is_facilitator(&ctx.accounts.authority.key()),
+            PaymentError::NotFacilitator
+        );
+    }
+
+    let amount = ctx.accounts.payment.amount;
+    let fee_refunded = if refund_fee { ctx.accounts.payment.platform_fee } else { 0 };
+
+    // A refunded fee was never earned, so the referrer's credit on it is reversed.
+    // Any part already withdrawn comes out of the platform's share.
+    let referral_fee = ctx.accounts.payment.referral_fee;
+    if refund_fee && referral_fee > 0 {
+        let accrual = ctx.accounts.referral_accrual
+            .as_mut()
+            .ok_or(PaymentError::ReferralAccountMissing)?;
+        let reversed = referral_fee.min(accrual.accrued);
+        accrual.accrued -= reversed;
+        accrual.total_earned = accrual.total_earned.saturating_sub(referral_fee);
+
+        let accepted_mint = &mut ctx.accounts.accepted_mint;
+        accepted_mint.referral_liability = accepted_mint.referral_liability.saturating_sub(reversed);
+        ctx.accounts.payment.referral_fee = 0;
+    }
+
+    // The fee comes out of the platform's own share; credit other referrers are
+    // still owed must stay in the treasury
+    if fee_refunded > 0 {
+        let available = ctx.accounts.fee_treasury.amount
+            .saturating_sub(ctx.accounts.accepted_mint.referral_liability);
+        require!(available >= fee_refunded, PaymentError::InsufficientTreasury);
+    }
+
+    let decimals = ctx.accounts.mint.decimals;
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+
+    // Return the escrow to the payer
+    let escrow_seeds = &[
+        b"escrow_authority".as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+    let escrow_signer = &[&escrow_seeds[..]];
+
+    refund_escrow_to_payer(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.payer_token_account.to_account_info(),
+        ctx.accounts.escrow_authority.to_account_info(),
+        cpi_program.clone(),
+        escrow_signer,
+        amount,
+    )?;
+
+    // Optionally return the platform fee from the treasury
+    if fee_refunded > 0 {
+        let platform_seeds = &[
+            b"platform_authority".as_ref(),
+            &[ctx.bumps.platform_authority],
+        ];
+        let platform_signer = &[&platform_seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.fee_treasury.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.payer_token_account.to_account_info(),
+            authority: ctx.accounts.platform_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, platform_signer);
+        token_interface::transfer_checked(cpi_ctx, fee_refunded, decimals)?;
+    }
+
+    let payment = &mut ctx.accounts.payment;
+    payment.state = PaymentState::Failed;
+    payment.failure_reason = Some(reason);
+    payment.result_cid = error_cid.clone();
+
+    emit!(TaskFailed {
+        payment_id: payment.payment_id,
+        payer: payment.payer,
+        recipient: payment.recipient,
+        failed_by: ctx.accounts.authority.key(),
+        reason,
+        error_cid,
+        amount_refunded: amount,
+        fee_refunded,
+        failed_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Task failed: {} - {:?}, {} refunded to payer", payment.payment_id, reason, amount);
+    Ok(())
+}
+
+#[event]
+pub struct TaskFailed {
+    pub payment_id: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub failed_by: Pubkey,
+    pub reason: FailureReason,
+    pub error_cid: String,
+    pub amount_refunded: u64,
+    pub fee_refunded: u64,
+    pub failed_at: i64,
+}
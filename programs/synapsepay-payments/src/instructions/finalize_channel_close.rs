@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::Channel;
+use super::close_channel::{refund_and_close_escrow, ChannelClosed};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct FinalizeChannelClose<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = payer @ PaymentError::Unauthorized,
+        constraint = channel.close_requested_at != 0 @ PaymentError::ChannelCloseNotRequested,
+        close = payer
+    )]
+    pub channel: Account<'info, Channel>,
+
+    /// Payment token mint, receives any withheld transfer fees on close
+    #[account(
+        mut,
+        address = channel.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Channel escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"channel_escrow", channel.key().as_ref()],
+        bump,
+        constraint = channel_escrow.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub channel_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account, receives the unspent deposit
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == channel.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payer.key() @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<FinalizeChannelClose>) -> Result<()> {
+    let channel = &ctx.accounts.channel;
+
+    let closes_at = channel.close_requested_at
+        .checked_add(channel.close_timeout)
+        .ok_or(PaymentError::MathOverflow)?;
+    require!(
+        Clock::get()?.unix_timestamp >= closes_at,
+        PaymentError::ChannelTimeoutNotElapsed
+    );
+
+    let refunded = refund_and_close_escrow(
+        &mut ctx.accounts.channel_escrow,
+        &ctx.accounts.mint,
+        &ctx.accounts.payer_token_account,
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.bumps.escrow_authority,
+    )?;
+
+    emit!(ChannelClosed {
+        channel_id: channel.channel_id,
+        redeemed: channel.redeemed,
+        refunded,
+        unilateral: true,
+    });
+
+    msg!("Channel closed by payer: {} ({} refunded)", channel.channel_id, refunded);
+    Ok(())
+}
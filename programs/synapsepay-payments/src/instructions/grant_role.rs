@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::{PlatformRole, state::PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<GrantRole>, role: PlatformRole, member: Pubkey) -> Result<()> {
+    let (members, max_members) = ctx.accounts.platform_config.role_members_mut(&role);
+
+    require!(!members.contains(&member), PaymentError::RoleAlreadyGranted);
+    require!(members.len() < max_members, PaymentError::RoleListFull);
+
+    members.push(member);
+
+    msg!("Role {:?} granted to {}", role, member);
+    Ok(())
+}
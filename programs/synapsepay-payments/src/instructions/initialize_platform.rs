@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Mint};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{AcceptedMint, PlatformConfig};
+use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
 pub struct InitializePlatform<'info> {
@@ -23,28 +25,76 @@ pub struct InitializePlatform<'info> {
     pub escrow_authority: UncheckedAccount<'info>,
 
     /// USDC mint
-    pub usdc_mint: Account<'info, Mint>,
+    pub usdc_mint: InterfaceAccount<'info, Mint>,
 
-    /// Platform fee treasury token account
+    /// First accepted mint entry
     #[account(
         init,
         payer = admin,
-        seeds = [b"fee_treasury"],
+        space = AcceptedMint::LEN,
+        seeds = [b"accepted_mint", usdc_mint.key().as_ref()],
+        bump,
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Platform fee treasury token account for USDC
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"fee_treasury", usdc_mint.key().as_ref()],
         bump,
         token::mint = usdc_mint,
         token::authority = platform_authority,
+        token::token_program = token_program,
     )]
-    pub fee_treasury: Account<'info, TokenAccount>,
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Platform fee configuration
+    #[account(
+        init,
+        payer = admin,
+        space = PlatformConfig::LEN,
+        seeds = [b"platform_config"],
+        bump,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn handler(ctx: Context<InitializePlatform>) -> Result<()> {
+pub fn handler(ctx: Context<InitializePlatform>, fee_bps: u16, min_fee: u64) -> Result<()> {
+    require!(fee_bps as u64 <= PlatformConfig::BPS_DENOMINATOR, PaymentError::InvalidFeeBps);
+
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.admin = ctx.accounts.admin.key();
+    platform_config.pending_admin = None;
+    platform_config.facilitators = Vec::new();
+    platform_config.refund_operators = Vec::new();
+    platform_config.arbiters = Vec::new();
+    platform_config.fee_bps = fee_bps;
+    platform_config.fee_tiers = Vec::new();
+    platform_config.min_fee = min_fee;
+    platform_config.pending_timeout = PlatformConfig::DEFAULT_PENDING_TIMEOUT;
+    platform_config.dispute_window = PlatformConfig::DEFAULT_DISPUTE_WINDOW;
+    platform_config.challenge_window = PlatformConfig::DEFAULT_CHALLENGE_WINDOW;
+    platform_config.referral_share_bps = 0;
+    platform_config.bump = ctx.bumps.platform_config;
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.mint = ctx.accounts.usdc_mint.key();
+    accepted_mint.fee_treasury = ctx.accounts.fee_treasury.key();
+    accepted_mint.is_active = true;
+    accepted_mint.referral_liability = 0;
+    accepted_mint.added_at = Clock::get()?.unix_timestamp;
+    accepted_mint.bump = ctx.bumps.accepted_mint;
+
     msg!("Platform initialized successfully");
     msg!("Platform Authority: {}", ctx.accounts.platform_authority.key());
     msg!("Escrow Authority: {}", ctx.accounts.escrow_authority.key());
+    msg!("Accepted Mint: {}", ctx.accounts.usdc_mint.key());
     msg!("Fee Treasury: {}", ctx.accounts.fee_treasury.key());
+    msg!("Platform Fee: {} bps (min {})", fee_bps, min_fee);
     Ok(())
 }
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::{PlatformConfig, ReceiptTree};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct InitializeReceiptTree<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ReceiptTree::LEN,
+        seeds = [b"receipt_tree"],
+        bump
+    )]
+    pub receipt_tree: Box<Account<'info, ReceiptTree>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeReceiptTree>) -> Result<()> {
+    let receipt_tree = &mut ctx.accounts.receipt_tree;
+    receipt_tree.initialize(ctx.bumps.receipt_tree);
+
+    msg!("Receipt tree initialized: depth {}", ReceiptTree::DEPTH);
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::{PaymentState, state::{Payment, Invoice, ReceiptLeaf, ReceiptTree}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct MintCompressedReceipt<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = payment.payer == payer.key() @ PaymentError::Unauthorized,
+        constraint = payment.state == PaymentState::Completed @ PaymentError::InvalidState
+    )]
+    pub payment: Account<'info, Payment>,
+
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    #[account(
+        mut,
+        seeds = [b"receipt_tree"],
+        bump = receipt_tree.bump
+    )]
+    pub receipt_tree: Box<Account<'info, ReceiptTree>>,
+}
+
+pub fn handler(ctx: Context<MintCompressedReceipt>) -> Result<()> {
+    let payment = &mut ctx.accounts.payment;
+    let receipt_tree = &mut ctx.accounts.receipt_tree;
+    require!(!receipt_tree.is_full(), PaymentError::ReceiptTreeFull);
+
+    // Same data a receipt account would hold, committed to as a single leaf
+    let leaf = ReceiptLeaf {
+        payment: payment.key(),
+        payer: payment.payer,
+        agent_id: ctx.accounts.invoice.agent_id.clone(),
+        amount: payment.amount + payment.platform_fee,
+        result_cid: payment.result_cid.clone(),
+        slot: Clock::get()?.slot,
+    };
+    let leaf_index = receipt_tree.append(leaf.hash());
+
+    // Update payment state
+    payment.state = PaymentState::ReceiptMinted;
+
+    // Indexers rebuild the tree from these events to serve proofs
+    emit!(ReceiptAppended {
+        leaf_index,
+        root: receipt_tree.root,
+        leaf,
+    });
+
+    msg!("Compressed receipt {} for payment: {}", leaf_index, payment.payment_id);
+    Ok(())
+}
+
+#[event]
+pub struct ReceiptAppended {
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+    pub leaf: ReceiptLeaf,
+}
@@ -1,4 +1,15 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_2022::{self, spl_token_2022, InitializeMint2, MintTo, SetAuthority, Token2022};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_2022_extensions::{
+    self, spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::{Field, TokenMetadata},
+    MetadataPointerInitialize, NonTransferableMintInitialize, TokenMetadataInitialize,
+    TokenMetadataUpdateField,
+};
 use crate::{PaymentState, state::{Payment, Receipt, Invoice}};
 use super::create_invoice::PaymentError;
 
@@ -14,6 +25,9 @@ pub struct MintReceipt<'info> {
     )]
     pub payment: Account<'info, Payment>,
 
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
     pub invoice: Account<'info, Invoice>,
 
     #[account(
@@ -25,6 +39,37 @@ pub struct MintReceipt<'info> {
     )]
     pub receipt: Account<'info, Receipt>,
 
+    /// Soulbound Token-2022 receipt mint, created by this instruction
+    /// CHECK: PDA, initialised below through the token program
+    #[account(
+        mut,
+        seeds = [b"receipt_mint", payment.key().as_ref()],
+        bump,
+    )]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// Receipt authority PDA - mint and metadata update authority
+    /// CHECK: PDA authority
+    #[account(
+        seeds = [b"receipt_authority"],
+        bump,
+    )]
+    pub receipt_authority: UncheckedAccount<'info>,
+
+    /// Payer's associated token account for the receipt mint
+    /// CHECK: Address is checked; created below by the associated token program
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &payer.key(),
+            &receipt_mint.key(),
+            &token_program.key()
+        ) @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_receipt_account: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -37,6 +82,7 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
     receipt.receipt_id = receipt.key();
     receipt.payment = payment.key();
     receipt.payer = payment.payer;
+    receipt.mint = ctx.accounts.receipt_mint.key();
     receipt.agent_id = invoice.agent_id.clone();
     receipt.amount = payment.amount + payment.platform_fee;
     receipt.result_cid = payment.result_cid.clone();
@@ -44,10 +90,217 @@ pub fn handler(ctx: Context<MintReceipt>) -> Result<()> {
     receipt.slot = clock.slot;
     receipt.bump = ctx.bumps.receipt;
 
+    let payment_key = payment.key();
+    let mint_seeds = &[
+        b"receipt_mint".as_ref(),
+        payment_key.as_ref(),
+        &[ctx.bumps.receipt_mint],
+    ];
+    let authority_seeds = &[
+        b"receipt_authority".as_ref(),
+        &[ctx.bumps.receipt_authority],
+    ];
+    let authority_signer = &[&authority_seeds[..]];
+
+    let mint_info = ctx.accounts.receipt_mint.to_account_info();
+    let authority_info = ctx.accounts.receipt_authority.to_account_info();
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    // Metadata readable by wallets and third parties without our account layout
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(authority_info.key()))?,
+        mint: mint_info.key(),
+        name: Receipt::NFT_NAME.to_string(),
+        symbol: Receipt::NFT_SYMBOL.to_string(),
+        uri: format!("ipfs://{}", receipt.result_cid),
+        additional_metadata: vec![
+            ("agent_id".to_string(), receipt.agent_id.clone()),
+            ("amount".to_string(), receipt.amount.to_string()),
+            ("result_cid".to_string(), receipt.result_cid.clone()),
+            ("slot".to_string(), receipt.slot.to_string()),
+        ],
+    };
+
+    // Allocate the mint with room for its extensions, funded for the metadata
+    // the token program reallocates into when it is written
+    let mint_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::NonTransferable,
+        ExtensionType::MetadataPointer,
+    ])?;
+    let lamports = Rent::get()?.minimum_balance(mint_len + metadata.tlv_size_of()?);
+
+    create_pda_account(
+        ctx.accounts.payer.to_account_info(),
+        mint_info.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        lamports,
+        mint_len as u64,
+        &token_program.key(),
+        &[&mint_seeds[..]],
+    )?;
+
+    // Soulbound: the receipt can never leave the payer's wallet
+    token_2022_extensions::non_transferable_mint_initialize(CpiContext::new(
+        token_program.clone(),
+        NonTransferableMintInitialize {
+            token_program_id: token_program.clone(),
+            mint: mint_info.clone(),
+        },
+    ))?;
+
+    token_2022_extensions::metadata_pointer_initialize(
+        CpiContext::new(
+            token_program.clone(),
+            MetadataPointerInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint_info.clone(),
+            },
+        ),
+        Some(authority_info.key()),
+        Some(mint_info.key()),
+    )?;
+
+    token_2022::initialize_mint2(
+        CpiContext::new(token_program.clone(), InitializeMint2 { mint: mint_info.clone() }),
+        0,
+        &authority_info.key(),
+        None,
+    )?;
+
+    token_2022_extensions::token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                token_program_id: token_program.clone(),
+                metadata: mint_info.clone(),
+                update_authority: authority_info.clone(),
+                mint_authority: authority_info.clone(),
+                mint: mint_info.clone(),
+            },
+            authority_signer,
+        ),
+        metadata.name.clone(),
+        metadata.symbol.clone(),
+        metadata.uri.clone(),
+    )?;
+
+    for (key, value) in metadata.additional_metadata {
+        token_2022_extensions::token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    token_program_id: token_program.clone(),
+                    metadata: mint_info.clone(),
+                    update_authority: authority_info.clone(),
+                },
+                authority_signer,
+            ),
+            Field::Key(key),
+            value,
+        )?;
+    }
+
+    // Mint the single receipt token to the payer, then fix the supply at one
+    associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        associated_token::Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.payer_receipt_account.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+            mint: mint_info.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: token_program.clone(),
+        },
+    ))?;
+
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: mint_info.clone(),
+                to: ctx.accounts.payer_receipt_account.to_account_info(),
+                authority: authority_info.clone(),
+            },
+            authority_signer,
+        ),
+        1,
+    )?;
+
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            token_program,
+            SetAuthority {
+                current_authority: authority_info,
+                account_or_mint: mint_info,
+            },
+            authority_signer,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
     // Update payment state
     payment.state = PaymentState::ReceiptMinted;
 
     msg!("Receipt minted: {} for payment: {}", receipt.receipt_id, payment.payment_id);
+    msg!("Receipt NFT: {}", receipt.mint);
     Ok(())
 }
 
+/// Create a PDA account the way Anchor's `init` does.
+///
+/// The address is predictable, so anyone may have sent it lamports already,
+/// which makes `create_account` fail. In that case the balance is topped up to
+/// `lamports` and the account is allocated and assigned instead.
+fn create_pda_account<'info>(
+    payer: AccountInfo<'info>,
+    account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        return system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program,
+                CreateAccount { from: payer, to: account },
+                signer_seeds,
+            ),
+            lamports,
+            space,
+            owner,
+        );
+    }
+
+    let top_up = lamports.saturating_sub(current_lamports);
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer { from: payer, to: account.clone() },
+            ),
+            top_up,
+        )?;
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate { account_to_allocate: account.clone() },
+            signer_seeds,
+        ),
+        space,
+    )?;
+
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program,
+            Assign { account_to_assign: account },
+            signer_seeds,
+        ),
+        owner,
+    )
+}
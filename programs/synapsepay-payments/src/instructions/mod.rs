@@ -7,6 +7,42 @@ pub mod mint_receipt;
 pub mod claim_payment;
 pub mod refund_payment;
 pub mod withdraw_fees;
+pub mod update_platform_config;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod grant_role;
+pub mod revoke_role;
+pub mod expire_invoice;
+pub mod expire_payment;
+pub mod close_payment;
+pub mod close_invoice;
+pub mod close_receipt;
+pub mod add_accepted_mint;
+pub mod update_accepted_mint;
+pub mod open_dispute;
+pub mod resolve_dispute;
+pub mod accept_result;
+pub mod refund_overdue_payment;
+pub mod fail_task;
+pub mod settle_split;
+pub mod capture_payment;
+pub mod capture_usage;
+pub mod initialize_receipt_tree;
+pub mod mint_compressed_receipt;
+pub mod verify_receipt_proof;
+pub mod open_channel;
+pub mod checkpoint_channel;
+pub mod close_channel;
+pub mod request_channel_close;
+pub mod finalize_channel_close;
+pub mod create_stream;
+pub mod withdraw_stream;
+pub mod stop_stream;
+pub mod set_referral_share;
+pub mod withdraw_referral_fees;
+pub mod set_fee_tiers;
+pub mod add_integrator;
+pub mod update_integrator;
 
 pub use initialize_platform::*;
 pub use create_invoice::*;
@@ -17,3 +53,39 @@ pub use mint_receipt::*;
 pub use claim_payment::*;
 pub use refund_payment::*;
 pub use withdraw_fees::*;
+pub use update_platform_config::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use grant_role::*;
+pub use revoke_role::*;
+pub use expire_invoice::*;
+pub use expire_payment::*;
+pub use close_payment::*;
+pub use close_invoice::*;
+pub use close_receipt::*;
+pub use add_accepted_mint::*;
+pub use update_accepted_mint::*;
+pub use open_dispute::*;
+pub use resolve_dispute::*;
+pub use accept_result::*;
+pub use refund_overdue_payment::*;
+pub use fail_task::*;
+pub use settle_split::*;
+pub use capture_payment::*;
+pub use capture_usage::*;
+pub use initialize_receipt_tree::*;
+pub use mint_compressed_receipt::*;
+pub use verify_receipt_proof::*;
+pub use open_channel::*;
+pub use checkpoint_channel::*;
+pub use close_channel::*;
+pub use request_channel_close::*;
+pub use finalize_channel_close::*;
+pub use create_stream::*;
+pub use withdraw_stream::*;
+pub use stop_stream::*;
+pub use set_referral_share::*;
+pub use withdraw_referral_fees::*;
+pub use set_fee_tiers::*;
+pub use add_integrator::*;
+pub use update_integrator::*;
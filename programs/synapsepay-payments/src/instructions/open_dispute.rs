@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::{PaymentState, state::{Payment, PlatformConfig}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.payer == payer.key() @ PaymentError::Unauthorized,
+        constraint = payment.state == PaymentState::Completed || payment.state == PaymentState::ReceiptMinted @ PaymentError::InvalidState
+    )]
+    pub payment: Account<'info, Payment>,
+}
+
+pub fn handler(ctx: Context<OpenDispute>) -> Result<()> {
+    let payment = &mut ctx.accounts.payment;
+    let clock = Clock::get()?;
+
+    require!(!payment.result_accepted, PaymentError::ResultAlreadyAccepted);
+
+    let window_ends_at = payment
+        .completed_at
+        .checked_add(ctx.accounts.platform_config.dispute_window)
+        .ok_or(PaymentError::MathOverflow)?;
+    require!(clock.unix_timestamp <= window_ends_at, PaymentError::DisputeWindowClosed);
+
+    // Disputed payments cannot be claimed until an arbiter resolves them
+    payment.state = PaymentState::Disputed;
+
+    emit!(DisputeOpened {
+        payment_id: payment.payment_id,
+        payer: payment.payer,
+        recipient: payment.recipient,
+        amount: payment.amount,
+        opened_at: clock.unix_timestamp,
+    });
+
+    msg!("Dispute opened: {}", payment.payment_id);
+    Ok(())
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub payment_id: Pubkey,
+    pub payer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub opened_at: i64,
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.pending_admin = Some(new_admin);
+
+    msg!("Admin handover proposed: {} -> {}", platform_config.admin, new_admin);
+    Ok(())
+}
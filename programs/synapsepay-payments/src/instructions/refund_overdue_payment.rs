@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentState, escrow::refund_escrow_to_payer, state::{Invoice, Payment}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct RefundOverduePayment<'info> {
+    /// Anyone may refund a payment the agent never completed
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState
+    )]
+    pub payment: Account<'info, Payment>,
+
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account (original payer)
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<RefundOverduePayment>) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp > ctx.accounts.invoice.execution_deadline,
+        PaymentError::DeadlineNotReached
+    );
+
+    let payment = &mut ctx.accounts.payment;
+    let amount = payment.amount;
+
+    // Transfer the escrow back to the payer
+    let seeds = &[
+        b"escrow_authority".as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    refund_escrow_to_payer(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.payer_token_account.to_account_info(),
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        amount,
+    )?;
+
+    payment.state = PaymentState::Refunded;
+
+    emit!(OverduePaymentRefunded {
+        payment_id: payment.payment_id,
+        payer: payment.payer,
+        amount,
+        execution_deadline: ctx.accounts.invoice.execution_deadline,
+        refunded_at: clock.unix_timestamp,
+    });
+
+    msg!("Overdue payment refunded: {} - {} returned to payer", payment.payment_id, amount);
+    Ok(())
+}
+
+#[event]
+pub struct OverduePaymentRefunded {
+    pub payment_id: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub execution_deadline: i64,
+    pub refunded_at: i64,
+}
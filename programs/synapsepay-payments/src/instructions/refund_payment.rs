@@ -1,30 +1,49 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{PaymentState, state::Payment};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentState, escrow::refund_escrow_to_payer, state::{Payment, PlatformConfig}};
 use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
 pub struct RefundPayment<'info> {
-    /// Platform authority or agent owner can initiate refund
+    /// Registered refund operator
     pub authority: Signer<'info>,
 
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.is_refund_operator(&authority.key()) @ PaymentError::NotRefundOperator
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
     #[account(
         mut,
-        constraint = payment.state == PaymentState::Failed || payment.state == PaymentState::Executing @ PaymentError::InvalidState
+        constraint = payment.state == PaymentState::Executing @ PaymentError::InvalidState
     )]
     pub payment: Account<'info, Payment>,
 
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
     /// Payment escrow account (PDA)
     #[account(
         mut,
         seeds = [b"escrow", payment.key().as_ref()],
         bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
     )]
-    pub escrow_account: Account<'info, TokenAccount>,
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
 
-    /// Payer's USDC token account (original payer)
-    #[account(mut)]
-    pub payer_token_account: Account<'info, TokenAccount>,
+    /// Payer's token account (original payer)
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Escrow authority PDA
     /// CHECK: PDA signer for escrow
@@ -34,7 +53,7 @@ pub struct RefundPayment<'info> {
     )]
     pub escrow_authority: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<RefundPayment>) -> Result<()> {
@@ -48,16 +67,15 @@ pub fn handler(ctx: Context<RefundPayment>) -> Result<()> {
     ];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.escrow_account.to_account_info(),
-        to: ctx.accounts.payer_token_account.to_account_info(),
-        authority: ctx.accounts.escrow_authority.to_account_info(),
-    };
-
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
-
-    token::transfer(cpi_ctx, amount)?;
+    refund_escrow_to_payer(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.payer_token_account.to_account_info(),
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        amount,
+    )?;
 
     // Update payment state
     payment.state = PaymentState::Refunded;
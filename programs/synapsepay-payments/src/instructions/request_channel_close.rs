@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::Channel;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct RequestChannelClose<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = payer @ PaymentError::Unauthorized,
+        constraint = channel.close_requested_at == 0 @ PaymentError::InvalidState
+    )]
+    pub channel: Account<'info, Channel>,
+}
+
+pub fn handler(ctx: Context<RequestChannelClose>) -> Result<()> {
+    let channel = &mut ctx.accounts.channel;
+
+    // Start the timeout; the agent can still redeem its latest voucher until it ends
+    channel.close_requested_at = Clock::get()?.unix_timestamp;
+
+    msg!(
+        "Channel close requested: {} (final after {}s)",
+        channel.channel_id,
+        channel.close_timeout
+    );
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentState, escrow::pay_out_shares, state::{Invoice, Payment, PlatformConfig}};
+use super::claim_payment::payee_transfers;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// Registered arbiter
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.is_arbiter(&arbiter.key()) @ PaymentError::NotArbiter
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Disputed @ PaymentError::InvalidState
+    )]
+    pub payment: Account<'info, Payment>,
+
+    /// Related invoice, carries the payee shares snapshotted at creation
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = recipient_token_account.owner == payment.recipient @ PaymentError::InvalidTokenOwner
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Remaining accounts: one token account per invoice payee, in the same order
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>, payer_share_bps: u16) -> Result<()> {
+    require!(
+        payer_share_bps as u64 <= PlatformConfig::BPS_DENOMINATOR,
+        PaymentError::InvalidShareBps
+    );
+
+    let amount = ctx.accounts.payment.amount;
+
+    // Payer share rounds down; the remainder goes to the recipient
+    let payer_amount = (amount as u128)
+        .checked_mul(payer_share_bps as u128)
+        .and_then(|v| v.checked_div(PlatformConfig::BPS_DENOMINATOR as u128))
+        .ok_or(PaymentError::MathOverflow)?;
+    let payer_amount = u64::try_from(payer_amount).map_err(|_| PaymentError::MathOverflow)?;
+    let recipient_amount = amount.checked_sub(payer_amount).ok_or(PaymentError::MathOverflow)?;
+
+    let seeds = &[
+        b"escrow_authority".as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // The recipient's share is divided with the invoice payees, as on a claim
+    let mut transfers = payee_transfers(
+        &ctx.accounts.invoice.payees,
+        ctx.remaining_accounts,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.payment.mint,
+        recipient_amount,
+    )?;
+    transfers.push((ctx.accounts.payer_token_account.to_account_info(), payer_amount));
+
+    pay_out_shares(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        transfers,
+    )?;
+
+    let payment = &mut ctx.accounts.payment;
+    payment.state = PaymentState::Resolved;
+    payment.payer_share = payer_amount;
+    payment.recipient_share = recipient_amount;
+
+    emit!(DisputeResolved {
+        payment_id: payment.payment_id,
+        arbiter: ctx.accounts.arbiter.key(),
+        payer_share_bps,
+        payer_amount,
+        recipient_amount,
+        resolved_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Dispute resolved: {} - {} to payer, {} to recipient",
+        payment.payment_id,
+        payer_amount,
+        recipient_amount
+    );
+    Ok(())
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub payment_id: Pubkey,
+    pub arbiter: Pubkey,
+    pub payer_share_bps: u16,
+    pub payer_amount: u64,
+    pub recipient_amount: u64,
+    pub resolved_at: i64,
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::{PlatformRole, state::PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<RevokeRole>, role: PlatformRole, member: Pubkey) -> Result<()> {
+    let (members, _) = ctx.accounts.platform_config.role_members_mut(&role);
+
+    let index = members
+        .iter()
+        .position(|key| key == &member)
+        .ok_or(PaymentError::RoleNotGranted)?;
+    members.remove(index);
+
+    msg!("Role {:?} revoked from {}", role, member);
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::{FeeTier, state::PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<SetFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+    require!(
+        fee_tiers.len() <= PlatformConfig::MAX_FEE_TIERS,
+        PaymentError::InvalidFeeTiers
+    );
+    let base_fee_bps = ctx.accounts.platform_config.fee_bps;
+    for (i, tier) in fee_tiers.iter().enumerate() {
+        // Tiers are discounts; volume must never raise a payer's rate
+        require!(tier.fee_bps <= base_fee_bps, PaymentError::InvalidFeeTiers);
+        // Thresholds must strictly increase so each volume maps to one tier
+        require!(
+            i == 0 || tier.min_volume > fee_tiers[i - 1].min_volume,
+            PaymentError::InvalidFeeTiers
+        );
+    }
+
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.fee_tiers = fee_tiers;
+
+    msg!("Fee tiers updated: {} tiers", platform_config.fee_tiers.len());
+    Ok(())
+}
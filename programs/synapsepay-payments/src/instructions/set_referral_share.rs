@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct SetReferralShare<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(ctx: Context<SetReferralShare>, referral_share_bps: u16) -> Result<()> {
+    require!(
+        referral_share_bps as u64 <= PlatformConfig::BPS_DENOMINATOR,
+        PaymentError::InvalidFeeBps
+    );
+
+    let platform_config = &mut ctx.accounts.platform_config;
+    platform_config.referral_share_bps = referral_share_bps;
+
+    msg!("Referral share updated: {} bps of the platform fee", referral_share_bps);
+    Ok(())
+}
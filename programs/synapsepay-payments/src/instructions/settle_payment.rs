@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{PaymentState, state::{Invoice, Payment}};
+use crate::{PaymentMode, PaymentState, state::{Invoice, PayerStats, Payment, PlatformConfig}};
 use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
@@ -23,6 +23,22 @@ pub struct SettlePayment<'info> {
     )]
     pub payment: Account<'info, Payment>,
 
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Payer's volume, created on their first settlement
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PayerStats::LEN,
+        seeds = [b"payer_stats", payer.key().as_ref()],
+        bump
+    )]
+    pub payer_stats: Box<Account<'info, PayerStats>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -34,8 +50,22 @@ pub fn handler(ctx: Context<SettlePayment>, signature: [u8; 64]) -> Result<()> {
     // Check expiry
     require!(clock.unix_timestamp < invoice.expires_at, PaymentError::InvoiceExpired);
 
-    // Calculate platform fee (5%)
-    let platform_fee = invoice.amount / 20;
+    // Pick the payer's volume tier now; the rate is locked in for this payment
+    let platform_config = &ctx.accounts.platform_config;
+    let payer_stats = &mut ctx.accounts.payer_stats;
+    if payer_stats.payer == Pubkey::default() {
+        payer_stats.payer = invoice.payer;
+        payer_stats.bump = ctx.bumps.payer_stats;
+    }
+    let volume = payer_stats.rolling_volume(clock.unix_timestamp);
+    let fee_bps = platform_config.fee_bps_for_volume(volume);
+
+    // Fixed payments pay the fee up front; holds escrow everything and pay
+    // the fee on the captured amount
+    let platform_fee = match invoice.mode {
+        PaymentMode::Fixed => platform_config.compute_fee_at(invoice.amount, fee_bps)?,
+        PaymentMode::Hold => 0,
+    };
     let net_amount = invoice.amount - platform_fee;
 
     // Update invoice state
@@ -46,7 +76,10 @@ pub fn handler(ctx: Context<SettlePayment>, signature: [u8; 64]) -> Result<()> {
     payment.invoice = invoice.key();
     payment.payer = invoice.payer;
     payment.recipient = invoice.recipient;
+    payment.mint = invoice.mint;
     payment.amount = net_amount;
+    payment.mode = invoice.mode;
+    payment.fee_bps = fee_bps;
     payment.platform_fee = platform_fee;
     payment.state = PaymentState::Pending;
     payment.tx_signature = signature;
@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentMode, PaymentState, escrow::pay_out_shares, state::{Invoice, Payment, PlatformConfig}};
+use super::claim_payment::payee_transfers;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct SettleSplit<'info> {
+    /// Payer or recipient proposing the split
+    pub authority: Signer<'info>,
+
+    /// The other party, or a registered facilitator
+    pub co_signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        constraint = payment.state == PaymentState::Executing
+            || payment.state == PaymentState::Completed
+            || payment.state == PaymentState::ReceiptMinted @ PaymentError::InvalidState,
+        constraint = payment.payer == authority.key() || payment.recipient == authority.key() @ PaymentError::Unauthorized,
+        // An uncaptured hold has not paid its platform fee yet
+        constraint = payment.mode == PaymentMode::Fixed || payment.state != PaymentState::Executing @ PaymentError::CaptureRequired
+    )]
+    pub payment: Account<'info, Payment>,
+
+    /// Related invoice, carries the payee shares snapshotted at creation
+    #[account(
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payment escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"escrow", payment.key().as_ref()],
+        bump,
+        constraint = escrow_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = escrow_account.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payer's token account
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payment.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == payment.mint @ PaymentError::InvalidMint,
+        constraint = recipient_token_account.owner == payment.recipient @ PaymentError::InvalidTokenOwner
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Remaining accounts: one token account per invoice payee, in the same order
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, SettleSplit<'info>>, recipient_amount: u64) -> Result<()> {
+    let payment = &ctx.accounts.payment;
+    let authority = ctx.accounts.authority.key();
+    let co_signer = ctx.accounts.co_signer.key();
+
+    // The co-signer must be the counterparty of `authority`, or a facilitator
+    let counterparty = if authority == payment.payer { payment.recipient } else { payment.payer };
+    require!(
+        co_signer == counterparty || ctx.accounts.platform_config.is_facilitator(&co_signer),
+        PaymentError::SplitNotApproved
+    );
+
+    let amount = payment.amount;
+    let payer_amount = amount
+        .checked_sub(recipient_amount)
+        .ok_or(PaymentError::InvalidSplitAmount)?;
+
+    let seeds = &[
+        b"escrow_authority".as_ref(),
+        &[ctx.bumps.escrow_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // The recipient's share is divided with the invoice payees, as on a claim
+    let mut transfers = payee_transfers(
+        &ctx.accounts.invoice.payees,
+        ctx.remaining_accounts,
+        ctx.accounts.recipient_token_account.to_account_info(),
+        ctx.accounts.payment.mint,
+        recipient_amount,
+    )?;
+    transfers.push((ctx.accounts.payer_token_account.to_account_info(), payer_amount));
+
+    pay_out_shares(
+        ctx.accounts.escrow_account.to_account_info(),
+        &ctx.accounts.mint,
+        ctx.accounts.escrow_authority.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        signer_seeds,
+        transfers,
+    )?;
+
+    let payment = &mut ctx.accounts.payment;
+    payment.state = PaymentState::SplitSettled;
+    payment.recipient_share = recipient_amount;
+    payment.payer_share = payer_amount;
+
+    emit!(PaymentSplit {
+        payment_id: payment.payment_id,
+        authority,
+        co_signer,
+        recipient_amount,
+        payer_amount,
+        settled_at: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Payment split: {} - {} to recipient, {} to payer",
+        payment.payment_id,
+        recipient_amount,
+        payer_amount
+    );
+    Ok(())
+}
+
+#[event]
+pub struct PaymentSplit {
+    pub payment_id: Pubkey,
+    pub authority: Pubkey,
+    pub co_signer: Pubkey,
+    pub recipient_amount: u64,
+    pub payer_amount: u64,
+    pub settled_at: i64,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use super::close_channel::refund_and_close_escrow;
+use super::withdraw_stream::*;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct StopStream<'info> {
+    /// Accrued payout accounts; the authority may be either side of the stream
+    pub withdraw: WithdrawStream<'info>,
+
+    /// CHECK: Stream payer, receives the unstreamed refund and reclaimed rent
+    #[account(
+        mut,
+        address = withdraw.stream.payer @ PaymentError::Unauthorized
+    )]
+    pub payer: UncheckedAccount<'info>,
+
+    /// Payer's token account, receives the unstreamed remainder
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == withdraw.stream.mint @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == withdraw.stream.payer @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub fn handler(ctx: Context<StopStream>) -> Result<()> {
+    let escrow_authority_bump = ctx.bumps.withdraw.escrow_authority;
+    let withdraw = &mut ctx.accounts.withdraw;
+
+    // Settle what has accrued so far, then refund the rest of the deposit
+    withdraw.withdraw_accrued(escrow_authority_bump)?;
+
+    let refunded = refund_and_close_escrow(
+        &mut withdraw.stream_escrow,
+        &withdraw.mint,
+        &ctx.accounts.payer_token_account,
+        ctx.accounts.payer.to_account_info(),
+        withdraw.escrow_authority.to_account_info(),
+        withdraw.token_program.to_account_info(),
+        escrow_authority_bump,
+    )?;
+
+    emit!(StreamStopped {
+        stream_id: withdraw.stream.stream_id,
+        stopped_by: withdraw.authority.key(),
+        streamed: withdraw.stream.withdrawn,
+        refunded,
+    });
+
+    msg!("Stream stopped: {} ({} refunded)", withdraw.stream.stream_id, refunded);
+    withdraw.stream.close(ctx.accounts.payer.to_account_info())
+}
+
+#[event]
+pub struct StreamStopped {
+    pub stream_id: Pubkey,
+    pub stopped_by: Pubkey,
+    pub streamed: u64,
+    pub refunded: u64,
+}
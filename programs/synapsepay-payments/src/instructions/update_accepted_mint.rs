@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{AcceptedMint, PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct UpdateAcceptedMint<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", accepted_mint.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+}
+
+pub fn handler(ctx: Context<UpdateAcceptedMint>, is_active: bool) -> Result<()> {
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.is_active = is_active;
+
+    // Existing invoices and payments in this mint can still be settled,
+    // claimed, refunded and withdrawn; only new invoices are blocked.
+    msg!("Accepted mint {} active: {}", accepted_mint.mint, is_active);
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{Integrator, PlatformConfig};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct UpdateIntegrator<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"integrator", integrator.wallet.as_ref()],
+        bump = integrator.bump
+    )]
+    pub integrator: Account<'info, Integrator>,
+}
+
+pub fn handler(ctx: Context<UpdateIntegrator>, is_active: bool) -> Result<()> {
+    let integrator = &mut ctx.accounts.integrator;
+    integrator.is_active = is_active;
+
+    // Invoices that already name this integrator still credit it on settlement;
+    // only new invoices are blocked.
+    msg!("Integrator {} active: {}", integrator.wallet, is_active);
+    Ok(())
+}
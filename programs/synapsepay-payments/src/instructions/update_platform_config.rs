@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::PlatformConfig;
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+pub fn handler(
+    ctx: Context<UpdatePlatformConfig>,
+    new_fee_bps: Option<u16>,
+    new_min_fee: Option<u64>,
+    new_pending_timeout: Option<i64>,
+    new_dispute_window: Option<i64>,
+    new_challenge_window: Option<i64>,
+) -> Result<()> {
+    let platform_config = &mut ctx.accounts.platform_config;
+
+    if let Some(fee_bps) = new_fee_bps {
+        require!(fee_bps as u64 <= PlatformConfig::BPS_DENOMINATOR, PaymentError::InvalidFeeBps);
+        platform_config.fee_bps = fee_bps;
+    }
+
+    if let Some(min_fee) = new_min_fee {
+        platform_config.min_fee = min_fee;
+    }
+
+    if let Some(pending_timeout) = new_pending_timeout {
+        require!(pending_timeout > 0, PaymentError::InvalidTimeout);
+        platform_config.pending_timeout = pending_timeout;
+    }
+
+    if let Some(dispute_window) = new_dispute_window {
+        require!(dispute_window >= 0, PaymentError::InvalidTimeout);
+        platform_config.dispute_window = dispute_window;
+    }
+
+    if let Some(challenge_window) = new_challenge_window {
+        require!(challenge_window >= 0, PaymentError::InvalidTimeout);
+        platform_config.challenge_window = challenge_window;
+    }
+
+    msg!("Platform config updated: fee {} bps, min fee {}", platform_config.fee_bps, platform_config.min_fee);
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::{PaymentState, state::Payment};
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{PaymentMode, PaymentState, ed25519::load_previous_ed25519, escrow::transfer_received, state::{AcceptedMint, Invoice, PayerStats, Payment, PlatformConfig, ReferralAccrual}};
 use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
@@ -15,60 +16,151 @@ pub struct VerifyPayment<'info> {
     )]
     pub payment: Account<'info, Payment>,
 
-    /// Payer's USDC token account
-    #[account(mut)]
-    pub payer_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = invoice.key() == payment.invoice @ PaymentError::InvalidState
+    )]
+    pub invoice: Account<'info, Invoice>,
+
+    /// Payment token mint
+    #[account(
+        address = payment.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Referrer's accrual for `mint`, required when the invoice has a referrer
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ReferralAccrual::LEN,
+        seeds = [b"referral", invoice.referrer.unwrap_or_default().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub referral_accrual: Option<Account<'info, ReferralAccrual>>,
 
-    /// Payment escrow account (PDA)
+    /// Payer's volume, feeds fee tier selection at settlement
     #[account(
         mut,
+        seeds = [b"payer_stats", payer.key().as_ref()],
+        bump = payer_stats.bump
+    )]
+    pub payer_stats: Box<Account<'info, PayerStats>>,
+
+    /// Payer's token account
+    #[account(
+        mut,
+        constraint = payer_token_account.mint == mint.key() @ PaymentError::InvalidMint,
+        constraint = payer_token_account.owner == payer.key() @ PaymentError::InvalidTokenOwner
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payment escrow account (PDA), created on first use
+    #[account(
+        init_if_needed,
+        payer = payer,
         seeds = [b"escrow", payment.key().as_ref()],
         bump,
+        token::mint = mint,
+        token::authority = escrow_authority,
+        token::token_program = token_program,
+    )]
+    pub escrow_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA owner of the escrow account
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
     )]
-    pub escrow_account: Account<'info, TokenAccount>,
+    pub escrow_authority: UncheckedAccount<'info>,
 
-    /// Platform fee treasury
+    /// Platform fee treasury for `mint`
     #[account(
         mut,
-        seeds = [b"fee_treasury"],
+        seeds = [b"fee_treasury", mint.key().as_ref()],
         bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury,
+        constraint = fee_treasury.mint == mint.key() @ PaymentError::InvalidMint
     )]
-    pub fee_treasury: Account<'info, TokenAccount>,
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Instructions sysvar for Ed25519 signature introspection
+    /// CHECK: Address is checked against the sysvar ID
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<VerifyPayment>) -> Result<()> {
     let payment = &mut ctx.accounts.payment;
-    
-    // Verify signature using Ed25519 instruction
-    // The signature verification is done via Ed25519Program instruction
-    // which should be passed before this instruction in the transaction
-    
-    // Calculate total amount (net + platform fee) - used for validation
-    let _total_amount = payment.amount + payment.platform_fee;
+    let invoice = &ctx.accounts.invoice;
+
+    // Verify the payer's payment intent via the preceding Ed25519Program instruction
+    let intent = load_previous_ed25519(&ctx.accounts.instructions_sysvar.to_account_info())?;
+    let expected_message = invoice.payment_intent_message();
+
+    require_keys_eq!(intent.pubkey, payment.payer, PaymentError::InvalidSignature);
+    require!(intent.message == expected_message, PaymentError::InvalidSignature);
+    require!(intent.signature == payment.tx_signature, PaymentError::InvalidSignature);
 
     // Transfer USDC from payer to escrow
-    let cpi_accounts_escrow = Transfer {
-        from: ctx.accounts.payer_token_account.to_account_info(),
-        to: ctx.accounts.escrow_account.to_account_info(),
-        authority: ctx.accounts.payer.to_account_info(),
-    };
+    payment.amount = transfer_received(
+        ctx.accounts.payer_token_account.to_account_info(),
+        &mut ctx.accounts.escrow_account,
+        &ctx.accounts.mint,
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        payment.amount,
+    )?;
 
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx_escrow = CpiContext::new(cpi_program.clone(), cpi_accounts_escrow);
+    // Transfer platform fee to treasury (holds pay it on capture)
+    if payment.platform_fee > 0 {
+        payment.platform_fee = transfer_received(
+            ctx.accounts.payer_token_account.to_account_info(),
+            &mut ctx.accounts.fee_treasury,
+            &ctx.accounts.mint,
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            payment.platform_fee,
+        )?;
+    }
+    if payment.mode == PaymentMode::Hold {
+        payment.authorized_amount = payment.amount;
+    }
 
-    token::transfer(cpi_ctx_escrow, payment.amount)?;
+    // Credit the referrer's share of the fee; it stays in the treasury until withdrawn
+    payment.referral_fee = credit_referral(
+        ctx.accounts.referral_accrual.as_mut(),
+        ctx.bumps.referral_accrual,
+        &mut ctx.accounts.accepted_mint,
+        &ctx.accounts.platform_config,
+        invoice,
+        payment.platform_fee,
+    )?;
 
-    // Transfer platform fee to treasury
-    let cpi_accounts_fee = Transfer {
-        from: ctx.accounts.payer_token_account.to_account_info(),
-        to: ctx.accounts.fee_treasury.to_account_info(),
-        authority: ctx.accounts.payer.to_account_info(),
-    };
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.payer_stats.record_payment(now, payment);
 
-    let cpi_ctx_fee = CpiContext::new(cpi_program, cpi_accounts_fee);
-    token::transfer(cpi_ctx_fee, payment.platform_fee)?;
+    // The agent's SLA runs from the moment the escrow is funded
+    ctx.accounts.invoice.execution_deadline = now
+        .checked_add(ctx.accounts.invoice.sla_seconds)
+        .ok_or(PaymentError::MathOverflow)?;
 
     // Update payment state
     payment.state = PaymentState::Executing;
@@ -76,3 +168,41 @@ pub fn handler(ctx: Context<VerifyPayment>) -> Result<()> {
     msg!("Payment verified and escrowed: {} USDC (+ {} fee)", payment.amount, payment.platform_fee);
     Ok(())
 }
+
+/// Credit the invoice referrer's share of a collected platform fee and return it.
+/// The credit stays in the fee treasury until the referrer withdraws it.
+pub(crate) fn credit_referral(
+    referral_accrual: Option<&mut Account<ReferralAccrual>>,
+    referral_bump: Option<u8>,
+    accepted_mint: &mut AcceptedMint,
+    platform_config: &PlatformConfig,
+    invoice: &Invoice,
+    platform_fee: u64,
+) -> Result<u64> {
+    let Some(referrer) = invoice.referrer else {
+        return Ok(0);
+    };
+    let referral_fee = platform_config.referral_fee(platform_fee);
+    if referral_fee == 0 {
+        return Ok(0);
+    }
+
+    let accrual = referral_accrual.ok_or(PaymentError::ReferralAccountMissing)?;
+    if accrual.referrer == Pubkey::default() {
+        accrual.referrer = referrer;
+        accrual.mint = accepted_mint.mint;
+        accrual.bump = referral_bump.ok_or(PaymentError::ReferralAccountMissing)?;
+    }
+    accrual.accrued = accrual.accrued
+        .checked_add(referral_fee)
+        .ok_or(PaymentError::MathOverflow)?;
+    accrual.total_earned = accrual.total_earned
+        .checked_add(referral_fee)
+        .ok_or(PaymentError::MathOverflow)?;
+
+    accepted_mint.referral_liability = accepted_mint.referral_liability
+        .checked_add(referral_fee)
+        .ok_or(PaymentError::MathOverflow)?;
+
+    Ok(referral_fee)
+}
@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+use crate::state::{ReceiptLeaf, ReceiptTree};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct VerifyReceiptProof<'info> {
+    #[account(
+        seeds = [b"receipt_tree"],
+        bump = receipt_tree.bump
+    )]
+    pub receipt_tree: Box<Account<'info, ReceiptTree>>,
+}
+
+pub fn handler(
+    ctx: Context<VerifyReceiptProof>,
+    leaf: ReceiptLeaf,
+    leaf_index: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.receipt_tree.verify(leaf.hash(), leaf_index, &proof),
+        PaymentError::InvalidReceiptProof
+    );
+
+    msg!("Receipt proof verified for payment: {}", leaf.payment);
+    Ok(())
+}
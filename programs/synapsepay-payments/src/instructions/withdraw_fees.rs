@@ -1,22 +1,46 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{AcceptedMint, PlatformConfig};
+use super::create_invoice::PaymentError;
 
 #[derive(Accounts)]
 pub struct WithdrawFees<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    /// Platform fee treasury account (PDA)
+    #[account(
+        has_one = admin @ PaymentError::NotAdmin,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Mint whose fees are withdrawn
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Platform fee treasury account for `mint` (PDA)
     #[account(
         mut,
-        seeds = [b"fee_treasury"],
+        seeds = [b"fee_treasury", mint.key().as_ref()],
         bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
     )]
-    pub fee_treasury: Account<'info, TokenAccount>,
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
 
-    /// Admin's USDC token account
-    #[account(mut)]
-    pub admin_token_account: Account<'info, TokenAccount>,
+    /// Admin's token account for `mint`
+    #[account(
+        mut,
+        constraint = admin_token_account.mint == mint.key() @ PaymentError::InvalidMint,
+        constraint = admin_token_account.owner == admin.key() @ PaymentError::InvalidTokenOwner
+    )]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Platform authority PDA
     /// CHECK: PDA signer for fee treasury
@@ -26,24 +50,26 @@ pub struct WithdrawFees<'info> {
     )]
     pub platform_authority: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<WithdrawFees>) -> Result<()> {
-    let fee_treasury = &ctx.accounts.fee_treasury;
-    let amount = fee_treasury.amount;
+    // Referral credit still owed stays in the treasury for referrers to withdraw
+    let amount = ctx.accounts.fee_treasury.amount
+        .saturating_sub(ctx.accounts.accepted_mint.referral_liability);
 
     require!(amount > 0, FeeError::NoFeesToWithdraw);
 
-    // Transfer all accumulated fees to admin
+    // Transfer the platform's accumulated fees to admin
     let seeds = &[
         b"platform_authority".as_ref(),
         &[ctx.bumps.platform_authority],
     ];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.fee_treasury.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.admin_token_account.to_account_info(),
         authority: ctx.accounts.platform_authority.to_account_info(),
     };
@@ -51,9 +77,9 @@ pub fn handler(ctx: Context<WithdrawFees>) -> Result<()> {
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
-    msg!("Fees withdrawn: {} USDC to admin", amount);
+    msg!("Fees withdrawn: {} of mint {} to admin", amount, ctx.accounts.mint.key());
     Ok(())
 }
 
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::state::{AcceptedMint, ReferralAccrual};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct WithdrawReferralFees<'info> {
+    pub referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = referrer @ PaymentError::Unauthorized,
+        seeds = [b"referral", referrer.key().as_ref(), mint.key().as_ref()],
+        bump = referral_accrual.bump
+    )]
+    pub referral_accrual: Account<'info, ReferralAccrual>,
+
+    /// Mint whose referral fees are withdrawn
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        mut,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Platform fee treasury for `mint`, holds the accrued credit
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Referrer's token account for `mint`
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == mint.key() @ PaymentError::InvalidMint,
+        constraint = referrer_token_account.owner == referrer.key() @ PaymentError::InvalidTokenOwner
+    )]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Platform authority PDA
+    /// CHECK: PDA signer for fee treasury
+    #[account(
+        seeds = [b"platform_authority"],
+        bump,
+    )]
+    pub platform_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawReferralFees>) -> Result<()> {
+    let amount = ctx.accounts.referral_accrual.accrued;
+    require!(amount > 0, PaymentError::NoReferralFees);
+
+    // Transfer the referrer's accrued credit out of the fee treasury
+    let seeds = &[
+        b"platform_authority".as_ref(),
+        &[ctx.bumps.platform_authority],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.fee_treasury.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.referrer_token_account.to_account_info(),
+        authority: ctx.accounts.platform_authority.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.referral_accrual.accrued = 0;
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.referral_liability = accepted_mint.referral_liability.saturating_sub(amount);
+
+    msg!("Referral fees withdrawn: {} of mint {} to {}", amount, ctx.accounts.mint.key(), ctx.accounts.referrer.key());
+    Ok(())
+}
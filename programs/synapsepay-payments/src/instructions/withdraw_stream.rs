@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::{escrow::pay_out_shares, state::{AcceptedMint, PlatformConfig, Stream}};
+use super::create_invoice::PaymentError;
+
+#[derive(Accounts)]
+pub struct WithdrawStream<'info> {
+    /// Stream recipient; either side when nested in `StopStream`.
+    /// Accrued funds only ever go to the recipient.
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = stream.recipient == authority.key() || stream.payer == authority.key() @ PaymentError::Unauthorized
+    )]
+    pub stream: Account<'info, Stream>,
+
+    /// Payment token mint, receives any withheld transfer fees on stop
+    #[account(
+        mut,
+        address = stream.mint @ PaymentError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Platform accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    /// Stream escrow account (PDA)
+    #[account(
+        mut,
+        seeds = [b"stream_escrow", stream.key().as_ref()],
+        bump,
+        constraint = stream_escrow.owner == escrow_authority.key() @ PaymentError::InvalidEscrowAuthority
+    )]
+    pub stream_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == stream.mint @ PaymentError::InvalidMint,
+        constraint = recipient_token_account.owner == stream.recipient @ PaymentError::InvalidTokenOwner
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Platform fee treasury for `mint`
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ PaymentError::InvalidFeeTreasury
+    )]
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Escrow authority PDA
+    /// CHECK: PDA signer for escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawStream>) -> Result<()> {
+    // Only the recipient decides when to pay the per-withdrawal fee
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        ctx.accounts.stream.recipient,
+        PaymentError::Unauthorized
+    );
+
+    let escrow_authority_bump = ctx.bumps.escrow_authority;
+    let withdrawn = ctx.accounts.withdraw_accrued(escrow_authority_bump)?;
+    require!(withdrawn > 0, PaymentError::InvalidAmount);
+    Ok(())
+}
+
+impl<'info> WithdrawStream<'info> {
+    /// Pay the recipient everything accrued but not yet withdrawn, less the
+    /// platform fee, and return the gross amount paid out
+    pub(crate) fn withdraw_accrued(&mut self, escrow_authority_bump: u8) -> Result<u64> {
+        let stream = &self.stream;
+        let accrued = stream.accrued_at(Clock::get()?.unix_timestamp);
+        let amount = accrued.saturating_sub(stream.withdrawn);
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        // Fees follow the rate in force when the stream was created
+        let platform_fee = PlatformConfig::fee_with(amount, stream.fee_bps, stream.min_fee)?;
+
+        let seeds = &[
+            b"escrow_authority".as_ref(),
+            &[escrow_authority_bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        pay_out_shares(
+            self.stream_escrow.to_account_info(),
+            &self.mint,
+            self.escrow_authority.to_account_info(),
+            self.token_program.to_account_info(),
+            signer_seeds,
+            [
+                (self.fee_treasury.to_account_info(), platform_fee),
+                (self.recipient_token_account.to_account_info(), amount - platform_fee),
+            ],
+        )?;
+
+        let stream = &mut self.stream;
+        stream.withdrawn = accrued;
+        stream.platform_fee = stream.platform_fee
+            .checked_add(platform_fee)
+            .ok_or(PaymentError::MathOverflow)?;
+
+        msg!("Stream {} withdrew {} ({} fee)", stream.stream_id, amount, platform_fee);
+        Ok(amount)
+    }
+}
@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer};
 
+pub mod ed25519;
+pub mod escrow;
 pub mod instructions;
 pub mod state;
 
 use instructions::*;
+use state::ReceiptLeaf;
 
 declare_id!("8yzR2Ze7t8NjH9b9wUUaUxkHLcrfogWqAzqbMSb1vZgP");
 
@@ -12,19 +14,65 @@ declare_id!("8yzR2Ze7t8NjH9b9wUUaUxkHLcrfogWqAzqbMSb1vZgP");
 pub mod synapsepay_payments {
     use super::*;
 
-    /// Initialize platform with fee treasury and authorities
-    pub fn initialize_platform(ctx: Context<InitializePlatform>) -> Result<()> {
-        instructions::initialize_platform::handler(ctx)
+    /// Initialize platform with the first accepted mint, its fee treasury, authorities and fee config
+    pub fn initialize_platform(
+        ctx: Context<InitializePlatform>,
+        fee_bps: u16,
+        min_fee: u64,
+    ) -> Result<()> {
+        instructions::initialize_platform::handler(ctx, fee_bps, min_fee)
+    }
+
+    /// Update platform fee configuration
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        new_fee_bps: Option<u16>,
+        new_min_fee: Option<u64>,
+        new_pending_timeout: Option<i64>,
+        new_dispute_window: Option<i64>,
+        new_challenge_window: Option<i64>,
+    ) -> Result<()> {
+        instructions::update_platform_config::handler(
+            ctx,
+            new_fee_bps,
+            new_min_fee,
+            new_pending_timeout,
+            new_dispute_window,
+            new_challenge_window,
+        )
+    }
+
+    /// Propose a new platform admin (step 1 of handover)
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    /// Accept a pending admin handover (step 2 of handover)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    /// Grant a platform role to a key
+    pub fn grant_role(ctx: Context<GrantRole>, role: PlatformRole, member: Pubkey) -> Result<()> {
+        instructions::grant_role::handler(ctx, role, member)
+    }
+
+    /// Revoke a platform role from a key
+    pub fn revoke_role(ctx: Context<RevokeRole>, role: PlatformRole, member: Pubkey) -> Result<()> {
+        instructions::revoke_role::handler(ctx, role, member)
     }
 
     /// Create a new payment invoice
     pub fn create_invoice(
         ctx: Context<CreateInvoice>,
         agent_id: String,
+        nonce: u64,
         amount: u64,
         expires_at: i64,
+        mode: PaymentMode,
+        referrer: Option<Pubkey>,
     ) -> Result<()> {
-        instructions::create_invoice::handler(ctx, agent_id, amount, expires_at)
+        instructions::create_invoice::handler(ctx, agent_id, nonce, amount, expires_at, mode, referrer)
     }
 
     /// Settle a payment after user signature
@@ -54,7 +102,7 @@ pub mod synapsepay_payments {
     }
 
     /// Claim payment as agent owner
-    pub fn claim_payment(ctx: Context<ClaimPayment>) -> Result<()> {
+    pub fn claim_payment<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimPayment<'info>>) -> Result<()> {
         instructions::claim_payment::handler(ctx)
     }
 
@@ -67,6 +115,193 @@ pub mod synapsepay_payments {
     pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
         instructions::withdraw_fees::handler(ctx)
     }
+
+    /// Expire an unsettled invoice past its expiry and reclaim rent
+    pub fn expire_invoice(ctx: Context<ExpireInvoice>) -> Result<()> {
+        instructions::expire_invoice::handler(ctx)
+    }
+
+    /// Expire a never-funded payment past the pending timeout and reclaim rent
+    pub fn expire_payment(ctx: Context<ExpirePayment>) -> Result<()> {
+        instructions::expire_payment::handler(ctx)
+    }
+
+    /// Close a terminal payment and its escrow account, returning rent to the payer
+    pub fn close_payment(ctx: Context<ClosePayment>) -> Result<()> {
+        instructions::close_payment::handler(ctx)
+    }
+
+    /// Close a terminal invoice, returning rent to the payer
+    pub fn close_invoice(ctx: Context<CloseInvoice>) -> Result<()> {
+        instructions::close_invoice::handler(ctx)
+    }
+
+    /// Close a receipt the payer no longer needs
+    pub fn close_receipt(ctx: Context<CloseReceipt>) -> Result<()> {
+        instructions::close_receipt::handler(ctx)
+    }
+
+    /// Accept a new payment mint and create its fee treasury
+    pub fn add_accepted_mint(ctx: Context<AddAcceptedMint>) -> Result<()> {
+        instructions::add_accepted_mint::handler(ctx)
+    }
+
+    /// Enable or disable an accepted mint for new invoices
+    pub fn update_accepted_mint(ctx: Context<UpdateAcceptedMint>, is_active: bool) -> Result<()> {
+        instructions::update_accepted_mint::handler(ctx, is_active)
+    }
+
+    /// Dispute a completed task within the dispute window (payer)
+    pub fn open_dispute(ctx: Context<OpenDispute>) -> Result<()> {
+        instructions::open_dispute::handler(ctx)
+    }
+
+    /// Split a disputed escrow between payer and recipient (arbiter)
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>,
+        payer_share_bps: u16,
+    ) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, payer_share_bps)
+    }
+
+    /// Accept a completed result and waive the challenge window (payer)
+    pub fn accept_result(ctx: Context<AcceptResult>) -> Result<()> {
+        instructions::accept_result::handler(ctx)
+    }
+
+    /// Refund an executing payment whose SLA deadline has passed (permissionless)
+    pub fn refund_overdue_payment(ctx: Context<RefundOverduePayment>) -> Result<()> {
+        instructions::refund_overdue_payment::handler(ctx)
+    }
+
+    /// Fail an executing task and refund the payer (facilitator or agent)
+    pub fn fail_task(
+        ctx: Context<FailTask>,
+        reason: FailureReason,
+        error_cid: Option<String>,
+        refund_fee: bool,
+    ) -> Result<()> {
+        instructions::fail_task::handler(ctx, reason, error_cid, refund_fee)
+    }
+
+    /// Pay part of the escrow to the recipient and return the rest to the payer
+    pub fn settle_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleSplit<'info>>,
+        recipient_amount: u64,
+    ) -> Result<()> {
+        instructions::settle_split::handler(ctx, recipient_amount)
+    }
+
+    /// Complete a hold payment, capturing the actual cost and releasing the rest
+    pub fn capture_payment(
+        ctx: Context<CapturePayment>,
+        result_cid: String,
+        capture_amount: u64,
+    ) -> Result<()> {
+        instructions::capture_payment::handler(ctx, result_cid, capture_amount)
+    }
+
+    /// Capture a hold from a usage report signed by the agent executor
+    pub fn capture_usage(ctx: Context<CaptureUsage>, result_cid: String, units: u64) -> Result<()> {
+        instructions::capture_usage::handler(ctx, result_cid, units)
+    }
+
+    /// Create the Merkle accumulator for compressed receipts
+    pub fn initialize_receipt_tree(ctx: Context<InitializeReceiptTree>) -> Result<()> {
+        instructions::initialize_receipt_tree::handler(ctx)
+    }
+
+    /// Record a receipt as a leaf in the receipt tree instead of a receipt account
+    pub fn mint_compressed_receipt(ctx: Context<MintCompressedReceipt>) -> Result<()> {
+        instructions::mint_compressed_receipt::handler(ctx)
+    }
+
+    /// Verify a compressed receipt against the receipt tree; callable by CPI
+    pub fn verify_receipt_proof(
+        ctx: Context<VerifyReceiptProof>,
+        leaf: ReceiptLeaf,
+        leaf_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::verify_receipt_proof::handler(ctx, leaf, leaf_index, proof)
+    }
+
+    /// Open a payment channel toward an agent with an escrowed deposit
+    pub fn open_channel(
+        ctx: Context<OpenChannel>,
+        agent_id: String,
+        nonce: u64,
+        deposit: u64,
+        close_timeout: i64,
+    ) -> Result<()> {
+        instructions::open_channel::handler(ctx, agent_id, nonce, deposit, close_timeout)
+    }
+
+    /// Redeem the latest payer-signed cumulative voucher, keeping the channel open
+    pub fn checkpoint_channel(ctx: Context<CheckpointChannel>, cumulative_amount: u64) -> Result<()> {
+        instructions::checkpoint_channel::handler(ctx, cumulative_amount)
+    }
+
+    /// Redeem the final voucher and close the channel, refunding the rest to the payer
+    pub fn close_channel(ctx: Context<CloseChannel>, cumulative_amount: u64) -> Result<()> {
+        instructions::close_channel::handler(ctx, cumulative_amount)
+    }
+
+    /// Start the payer's unilateral close timeout
+    pub fn request_channel_close(ctx: Context<RequestChannelClose>) -> Result<()> {
+        instructions::request_channel_close::handler(ctx)
+    }
+
+    /// Close the channel once the timeout has passed, refunding the rest to the payer
+    pub fn finalize_channel_close(ctx: Context<FinalizeChannelClose>) -> Result<()> {
+        instructions::finalize_channel_close::handler(ctx)
+    }
+
+    /// Start a per-second payment stream toward an agent with an escrowed deposit
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        agent_id: String,
+        nonce: u64,
+        deposit: u64,
+        rate_per_second: u64,
+    ) -> Result<()> {
+        instructions::create_stream::handler(ctx, agent_id, nonce, deposit, rate_per_second)
+    }
+
+    /// Pay the recipient what the stream has accrued so far
+    pub fn withdraw_stream(ctx: Context<WithdrawStream>) -> Result<()> {
+        instructions::withdraw_stream::handler(ctx)
+    }
+
+    /// Stop a stream, paying out what accrued and refunding the rest to the payer
+    pub fn stop_stream(ctx: Context<StopStream>) -> Result<()> {
+        instructions::stop_stream::handler(ctx)
+    }
+
+    /// Set the share of the platform fee credited to invoice referrers
+    pub fn set_referral_share(ctx: Context<SetReferralShare>, referral_share_bps: u16) -> Result<()> {
+        instructions::set_referral_share::handler(ctx, referral_share_bps)
+    }
+
+    /// Withdraw a referrer's accrued fee share from the fee treasury
+    pub fn withdraw_referral_fees(ctx: Context<WithdrawReferralFees>) -> Result<()> {
+        instructions::withdraw_referral_fees::handler(ctx)
+    }
+
+    /// Set the volume thresholds that lower a payer's platform fee
+    pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+        instructions::set_fee_tiers::handler(ctx, fee_tiers)
+    }
+
+    /// Register a wallet that may be named as an invoice referrer
+    pub fn add_integrator(ctx: Context<AddIntegrator>, wallet: Pubkey) -> Result<()> {
+        instructions::add_integrator::handler(ctx, wallet)
+    }
+
+    /// Enable or disable an integrator for new invoices
+    pub fn update_integrator(ctx: Context<UpdateIntegrator>, is_active: bool) -> Result<()> {
+        instructions::update_integrator::handler(ctx, is_active)
+    }
 }
 
 
@@ -81,6 +316,9 @@ pub enum PaymentState {
     Expired,
     Failed,
     Refunded,
+    Disputed,
+    Resolved,
+    SplitSettled,
 }
 
 impl Default for PaymentState {
@@ -88,3 +326,57 @@ impl Default for PaymentState {
         PaymentState::InvoiceCreated
     }
 }
+
+impl PaymentState {
+    /// States after which no funds remain in escrow
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            PaymentState::Claimed
+                | PaymentState::Refunded
+                | PaymentState::Expired
+                | PaymentState::Failed
+                | PaymentState::Resolved
+                | PaymentState::SplitSettled
+        )
+    }
+}
+
+/// How the invoice amount is charged
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PaymentMode {
+    /// The full amount is charged up front
+    #[default]
+    Fixed,
+    /// The amount is a maximum held in escrow; the actual cost is captured on completion
+    Hold,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FailureReason {
+    AgentError,
+    InvalidInput,
+    Timeout,
+    ResourceUnavailable,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum PlatformRole {
+    Facilitator,
+    RefundOperator,
+    Arbiter,
+}
+
+/// Fee rate applied to payers whose rolling 30-day volume reaches `min_volume`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FeeTier {
+    /// Rolling 30-day volume threshold in mint base units
+    pub min_volume: u64,
+    /// Platform fee in basis points at or above the threshold
+    pub fee_bps: u16,
+}
+
+impl FeeTier {
+    pub const LEN: usize = 8 + 2;
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+/// Token mint the platform accepts payments in
+#[account]
+#[derive(Default)]
+pub struct AcceptedMint {
+    /// Token mint
+    pub mint: Pubkey,
+    /// Fee treasury token account for this mint
+    pub fee_treasury: Pubkey,
+    /// Whether new invoices may use this mint
+    pub is_active: bool,
+    /// Referral credit held in the fee treasury but not yet withdrawn
+    pub referral_liability: u64,
+    /// When the mint was added
+    pub added_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl AcceptedMint {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // fee_treasury
+        1 + // is_active
+        8 + // referral_liability
+        8 + // added_at
+        1; // bump
+}
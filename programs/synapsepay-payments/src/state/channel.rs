@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// Unidirectional payment channel from a payer to one agent.
+/// The payer signs cumulative vouchers off-chain; the agent redeems the latest one.
+#[account]
+#[derive(Default)]
+pub struct Channel {
+    /// PDA derived ID
+    pub channel_id: Pubkey,
+    /// User wallet, signs vouchers
+    pub payer: Pubkey,
+    /// Agent owner, redeems vouchers
+    pub recipient: Pubkey,
+    /// Registry agent account
+    pub agent: Pubkey,
+    /// Payment token mint
+    pub mint: Pubkey,
+    /// Client-chosen nonce, shared with invoices and streams so a channel address is never reused
+    pub nonce: u64,
+    /// Total deposited into the channel escrow
+    pub deposit: u64,
+    /// Cumulative voucher amount already paid out (fees included)
+    pub redeemed: u64,
+    /// Platform fees taken from redemptions
+    pub platform_fee: u64,
+    /// Platform fee rate locked in when the channel was opened
+    pub fee_bps: u16,
+    /// Platform minimum fee locked in when the channel was opened
+    pub min_fee: u64,
+    /// Seconds the agent has to redeem after the payer requests a unilateral close
+    pub close_timeout: i64,
+    /// When the payer requested a unilateral close (0 = not requested)
+    pub close_requested_at: i64,
+    /// Creation time
+    pub opened_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl Channel {
+    /// Shortest unilateral close timeout a payer may choose
+    pub const MIN_CLOSE_TIMEOUT: i64 = 600;
+    /// Domain separator for signed vouchers
+    pub const VOUCHER_PREFIX: &'static [u8] = b"synapsepay:channel-voucher:v1";
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // channel_id
+        32 + // payer
+        32 + // recipient
+        32 + // agent
+        32 + // mint
+        8 + // nonce
+        8 + // deposit
+        8 + // redeemed
+        8 + // platform_fee
+        2 + // fee_bps
+        8 + // min_fee
+        8 + // close_timeout
+        8 + // close_requested_at
+        8 + // opened_at
+        1; // bump
+
+    /// Voucher signed by the payer:
+    /// prefix || channel_id || cumulative amount (u64 LE)
+    pub fn voucher_message(&self, cumulative_amount: u64) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::VOUCHER_PREFIX.len() + 32 + 8);
+        message.extend_from_slice(Self::VOUCHER_PREFIX);
+        message.extend_from_slice(self.channel_id.as_ref());
+        message.extend_from_slice(&cumulative_amount.to_le_bytes());
+        message
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// Wallet the platform has approved to earn referral fees
+#[account]
+#[derive(Default)]
+pub struct Integrator {
+    /// Integrator wallet, named as the referrer on invoices
+    pub wallet: Pubkey,
+    /// Whether new invoices may name this integrator
+    pub is_active: bool,
+    /// When the integrator was registered
+    pub added_at: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl Integrator {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        1 + // is_active
+        8 + // added_at
+        1; // bump
+}
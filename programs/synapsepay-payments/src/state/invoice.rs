@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::PaymentState;
+use synapsepay_registry::{Payee, state::Agent};
+use crate::{PaymentMode, PaymentState};
 
 #[account]
 #[derive(Default)]
@@ -10,17 +11,39 @@ pub struct Invoice {
     pub payer: Pubkey,
     /// Agent owner
     pub recipient: Pubkey,
+    /// Registry agent account
+    pub agent: Pubkey,
     /// Target agent
     pub agent_id: String,
-    /// USDC amount
+    /// Agent price at invoice creation
+    pub agent_price: u64,
+    /// Agent challenge window at invoice creation (0 = platform default)
+    pub challenge_window: i64,
+    /// Agent SLA at invoice creation, counted from funding
+    pub sla_seconds: i64,
+    /// Agent usage price per unit at invoice creation (0 = not metered)
+    pub unit_price: u64,
+    /// Agent key that signs usage reports
+    pub executor: Pubkey,
+    /// Agent revenue shares at invoice creation, paid out on claim
+    pub payees: Vec<Payee>,
+    /// Integrator credited with a share of the platform fee
+    pub referrer: Option<Pubkey>,
+    /// Payment token mint
+    pub mint: Pubkey,
+    /// Amount in mint base units (the maximum for holds)
     pub amount: u64,
+    /// Fixed charge or authorize-then-capture hold
+    pub mode: PaymentMode,
     /// Current state
     pub state: PaymentState,
     /// Expiration time
     pub expires_at: i64,
+    /// Latest time the agent may complete the task (funding time + SLA), set on verification
+    pub execution_deadline: i64,
     /// Creation time
     pub created_at: i64,
-    /// Replay protection nonce
+    /// Client-chosen replay protection nonce
     pub nonce: u64,
     /// Bump seed
     pub bump: u8,
@@ -28,16 +51,42 @@ pub struct Invoice {
 
 impl Invoice {
     pub const MAX_AGENT_ID_LEN: usize = 32;
+    /// Domain separator for signed payment intents
+    pub const PAYMENT_INTENT_PREFIX: &'static [u8] = b"synapsepay:payment-intent:v1";
     
     pub const LEN: usize = 8 + // discriminator
         32 + // invoice_id
         32 + // payer
         32 + // recipient
+        32 + // agent
         4 + Self::MAX_AGENT_ID_LEN + // agent_id
+        8 + // agent_price
+        8 + // challenge_window
+        8 + // sla_seconds
+        8 + // unit_price
+        32 + // executor
+        4 + Payee::LEN * Agent::MAX_PAYEES + // payees
+        1 + 32 + // referrer
+        32 + // mint
         8 + // amount
+        1 + // mode
         1 + // state
         8 + // expires_at
+        8 + // execution_deadline
         8 + // created_at
         8 + // nonce
         1; // bump
+
+    /// Canonical payment intent signed by the payer:
+    /// prefix || invoice_id || amount (u64 LE) || recipient || mint || expires_at (i64 LE)
+    pub fn payment_intent_message(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::PAYMENT_INTENT_PREFIX.len() + 32 + 8 + 32 + 32 + 8);
+        message.extend_from_slice(Self::PAYMENT_INTENT_PREFIX);
+        message.extend_from_slice(self.invoice_id.as_ref());
+        message.extend_from_slice(&self.amount.to_le_bytes());
+        message.extend_from_slice(self.recipient.as_ref());
+        message.extend_from_slice(self.mint.as_ref());
+        message.extend_from_slice(&self.expires_at.to_le_bytes());
+        message
+    }
 }
@@ -1,7 +1,25 @@
 pub mod invoice;
 pub mod payment;
 pub mod receipt;
+pub mod platform_config;
+pub mod nonce_tracker;
+pub mod accepted_mint;
+pub mod receipt_tree;
+pub mod channel;
+pub mod stream;
+pub mod referral_accrual;
+pub mod payer_stats;
+pub mod integrator;
 
 pub use invoice::*;
 pub use payment::*;
 pub use receipt::*;
+pub use platform_config::*;
+pub use nonce_tracker::*;
+pub use accepted_mint::*;
+pub use receipt_tree::*;
+pub use channel::*;
+pub use stream::*;
+pub use referral_accrual::*;
+pub use payer_stats::*;
+pub use integrator::*;
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Per-payer invoice, channel and stream nonce high-water mark
+#[account]
+#[derive(Default)]
+pub struct NonceTracker {
+    /// User wallet
+    pub payer: Pubkey,
+    /// Lowest nonce the payer may still use
+    pub next_nonce: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl NonceTracker {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // payer
+        8 + // next_nonce
+        1; // bump
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::state::Payment;
+
+/// Per-payer volume used to pick a fee tier
+#[account]
+#[derive(Default)]
+pub struct PayerStats {
+    /// User wallet
+    pub payer: Pubkey,
+    /// Volume funded over the payer's lifetime
+    pub lifetime_volume: u64,
+    /// Volume per day, indexed by day number modulo the window
+    pub daily_volume: [u64; PayerStats::WINDOW_DAYS],
+    /// Day number (unix time / 86400) of the most recent recorded volume
+    pub last_day: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl PayerStats {
+    /// Length of the rolling volume window in days
+    pub const WINDOW_DAYS: usize = 30;
+    pub const SECONDS_PER_DAY: i64 = 86_400;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // payer
+        8 + // lifetime_volume
+        8 * Self::WINDOW_DAYS + // daily_volume
+        8 + // last_day
+        1; // bump
+
+    /// Volume over the last `WINDOW_DAYS` days, today included
+    pub fn rolling_volume(&self, now: i64) -> u64 {
+        let today = now.div_euclid(Self::SECONDS_PER_DAY);
+        let window = Self::WINDOW_DAYS as i64;
+        let first_day = (self.last_day - window + 1).max(today - window + 1);
+
+        (first_day..=self.last_day)
+            .map(|day| self.daily_volume[day.rem_euclid(window) as usize])
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Count a payment's escrowed amount and fee toward the payer's volume once it is funded
+    pub fn record_payment(&mut self, now: i64, payment: &Payment) {
+        self.record(now, payment.amount.saturating_add(payment.platform_fee));
+    }
+
+    /// Add `amount` to today's bucket, clearing days that fell out of the window
+    pub fn record(&mut self, now: i64, amount: u64) {
+        let today = now.div_euclid(Self::SECONDS_PER_DAY);
+        let window = Self::WINDOW_DAYS as i64;
+
+        if today > self.last_day {
+            let first_stale = (self.last_day + 1).max(today - window + 1);
+            for day in first_stale..=today {
+                self.daily_volume[day.rem_euclid(window) as usize] = 0;
+            }
+            self.last_day = today;
+        }
+
+        let bucket = &mut self.daily_volume[today.rem_euclid(window) as usize];
+        *bucket = bucket.saturating_add(amount);
+        self.lifetime_volume = self.lifetime_volume.saturating_add(amount);
+    }
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::PaymentState;
+use crate::{FailureReason, PaymentMode, PaymentState};
 
 #[account]
 pub struct Payment {
@@ -11,18 +11,42 @@ pub struct Payment {
     pub payer: Pubkey,
     /// Agent owner
     pub recipient: Pubkey,
-    /// USDC amount
+    /// Payment token mint
+    pub mint: Pubkey,
+    /// Amount held in escrow for the recipient
     pub amount: u64,
-    /// Platform fee (5%)
+    /// Fixed charge or authorize-then-capture hold
+    pub mode: PaymentMode,
+    /// Amount authorized into escrow for holds
+    pub authorized_amount: u64,
+    /// Gross amount captured from a hold, including the platform fee
+    pub captured_amount: u64,
+    /// Fee rate applied at settlement, in basis points
+    pub fee_bps: u16,
+    /// Platform fee
     pub platform_fee: u64,
+    /// Part of the platform fee credited to the invoice referrer
+    pub referral_fee: u64,
+    /// Escrow paid to the recipient by a split or dispute settlement
+    pub recipient_share: u64,
+    /// Escrow returned to the payer by a split or dispute settlement
+    pub payer_share: u64,
     /// Current state
     pub state: PaymentState,
-    /// IPFS result CID
+    /// IPFS result CID (error CID for failed tasks)
     pub result_cid: String,
+    /// Why the task failed, if it did
+    pub failure_reason: Option<FailureReason>,
     /// Solana tx signature
     pub tx_signature: [u8; 64],
     /// Settlement time
     pub settled_at: i64,
+    /// Task completion time
+    pub completed_at: i64,
+    /// Earliest time the recipient may claim
+    pub claimable_at: i64,
+    /// Payer accepted the result, waiving the challenge window
+    pub result_accepted: bool,
     /// Bump seed
     pub bump: u8,
 }
@@ -34,12 +58,24 @@ impl Default for Payment {
             invoice: Pubkey::default(),
             payer: Pubkey::default(),
             recipient: Pubkey::default(),
+            mint: Pubkey::default(),
             amount: 0,
+            mode: PaymentMode::default(),
+            authorized_amount: 0,
+            captured_amount: 0,
+            fee_bps: 0,
             platform_fee: 0,
+            referral_fee: 0,
+            recipient_share: 0,
+            payer_share: 0,
             state: PaymentState::default(),
             result_cid: String::new(),
+            failure_reason: None,
             tx_signature: [0u8; 64],
             settled_at: 0,
+            completed_at: 0,
+            claimable_at: 0,
+            result_accepted: false,
             bump: 0,
         }
     }
@@ -47,17 +83,42 @@ impl Default for Payment {
 
 impl Payment {
     pub const MAX_RESULT_CID_LEN: usize = 64;
+    /// Domain separator for signed usage reports
+    pub const USAGE_REPORT_PREFIX: &'static [u8] = b"synapsepay:usage-report:v1";
     
     pub const LEN: usize = 8 + // discriminator
         32 + // payment_id
         32 + // invoice
         32 + // payer
         32 + // recipient
+        32 + // mint
         8 + // amount
+        1 + // mode
+        8 + // authorized_amount
+        8 + // captured_amount
+        2 + // fee_bps
         8 + // platform_fee
+        8 + // referral_fee
+        8 + // recipient_share
+        8 + // payer_share
         1 + // state
         4 + Self::MAX_RESULT_CID_LEN + // result_cid
+        1 + 1 + // failure_reason
         64 + // tx_signature
         8 + // settled_at
+        8 + // completed_at
+        8 + // claimable_at
+        1 + // result_accepted
         1; // bump
+
+    /// Usage report signed by the agent executor:
+    /// prefix || payment_id || units (u64 LE) || result_cid
+    pub fn usage_report_message(&self, units: u64, result_cid: &str) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::USAGE_REPORT_PREFIX.len() + 32 + 8 + result_cid.len());
+        message.extend_from_slice(Self::USAGE_REPORT_PREFIX);
+        message.extend_from_slice(self.payment_id.as_ref());
+        message.extend_from_slice(&units.to_le_bytes());
+        message.extend_from_slice(result_cid.as_bytes());
+        message
+    }
 }
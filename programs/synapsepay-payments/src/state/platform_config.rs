@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::{FeeTier, PlatformRole, instructions::create_invoice::PaymentError};
+
+#[account]
+#[derive(Default)]
+pub struct PlatformConfig {
+    /// Platform admin
+    pub admin: Pubkey,
+    /// Proposed admin awaiting acceptance
+    pub pending_admin: Option<Pubkey>,
+    /// Keys allowed to complete tasks
+    pub facilitators: Vec<Pubkey>,
+    /// Keys allowed to refund payments
+    pub refund_operators: Vec<Pubkey>,
+    /// Keys allowed to resolve disputes
+    pub arbiters: Vec<Pubkey>,
+    /// Platform fee in basis points
+    pub fee_bps: u16,
+    /// Volume discounts, ascending by threshold; below the first tier `fee_bps` applies
+    pub fee_tiers: Vec<FeeTier>,
+    /// Minimum fee in token base units
+    pub min_fee: u64,
+    /// Seconds a settled payment may stay unfunded before anyone can expire it
+    pub pending_timeout: i64,
+    /// Seconds after completion during which the payer may open a dispute
+    pub dispute_window: i64,
+    /// Default seconds a completed result must wait before it can be claimed
+    pub challenge_window: i64,
+    /// Share of the platform fee credited to the invoice referrer, in basis points
+    pub referral_share_bps: u16,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl PlatformConfig {
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    pub const MAX_FACILITATORS: usize = 10;
+    pub const MAX_REFUND_OPERATORS: usize = 10;
+    pub const MAX_ARBITERS: usize = 10;
+    pub const MAX_FEE_TIERS: usize = 5;
+    pub const DEFAULT_PENDING_TIMEOUT: i64 = 3600;
+    pub const DEFAULT_DISPUTE_WINDOW: i64 = 86_400;
+    pub const DEFAULT_CHALLENGE_WINDOW: i64 = 3600;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // admin
+        1 + 32 + // pending_admin
+        4 + 32 * Self::MAX_FACILITATORS + // facilitators
+        4 + 32 * Self::MAX_REFUND_OPERATORS + // refund_operators
+        4 + 32 * Self::MAX_ARBITERS + // arbiters
+        2 + // fee_bps
+        4 + FeeTier::LEN * Self::MAX_FEE_TIERS + // fee_tiers
+        8 + // min_fee
+        8 + // pending_timeout
+        8 + // dispute_window
+        8 + // challenge_window
+        2 + // referral_share_bps
+        1; // bump
+
+    pub fn is_facilitator(&self, key: &Pubkey) -> bool {
+        self.facilitators.contains(key)
+    }
+
+    pub fn is_refund_operator(&self, key: &Pubkey) -> bool {
+        self.refund_operators.contains(key)
+    }
+
+    pub fn is_arbiter(&self, key: &Pubkey) -> bool {
+        self.arbiters.contains(key)
+    }
+
+    /// Member list and capacity for a role
+    pub fn role_members_mut(&mut self, role: &PlatformRole) -> (&mut Vec<Pubkey>, usize) {
+        match role {
+            PlatformRole::Facilitator => (&mut self.facilitators, Self::MAX_FACILITATORS),
+            PlatformRole::RefundOperator => (&mut self.refund_operators, Self::MAX_REFUND_OPERATORS),
+            PlatformRole::Arbiter => (&mut self.arbiters, Self::MAX_ARBITERS),
+        }
+    }
+
+    /// Platform fee for `amount` at the base rate.
+    ///
+    /// Rounding rule: `amount * fee_bps / 10_000` is rounded down to the
+    /// nearest base unit, then raised to `min_fee`, and never exceeds `amount`.
+    pub fn compute_fee(&self, amount: u64) -> Result<u64> {
+        self.compute_fee_at(amount, self.fee_bps)
+    }
+
+    /// Platform fee for `amount` at `fee_bps`, same rounding as `compute_fee`
+    pub fn compute_fee_at(&self, amount: u64, fee_bps: u16) -> Result<u64> {
+        Self::fee_with(amount, fee_bps, self.min_fee)
+    }
+
+    /// Platform fee for `amount` at a rate and minimum locked in earlier,
+    /// same rounding as `compute_fee`
+    pub fn fee_with(amount: u64, fee_bps: u16, min_fee: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(fee_bps as u128)
+            .and_then(|v| v.checked_div(Self::BPS_DENOMINATOR as u128))
+            .ok_or(PaymentError::MathOverflow)?;
+        let fee = u64::try_from(fee).map_err(|_| PaymentError::MathOverflow)?;
+
+        Ok(fee.max(min_fee).min(amount))
+    }
+
+    /// Fee rate for a payer with `volume` over the rolling window.
+    ///
+    /// Tiers only ever discount: if the base rate was lowered below a tier
+    /// after the tiers were set, the base rate applies.
+    pub fn fee_bps_for_volume(&self, volume: u64) -> u16 {
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+            .map_or(self.fee_bps, |tier| tier.fee_bps.min(self.fee_bps))
+    }
+
+    /// Referrer's cut of a collected platform fee, rounded down
+    pub fn referral_fee(&self, platform_fee: u64) -> u64 {
+        (platform_fee as u128 * self.referral_share_bps as u128 / Self::BPS_DENOMINATOR as u128) as u64
+    }
+}
@@ -9,6 +9,8 @@ pub struct Receipt {
     pub payment: Pubkey,
     /// User wallet
     pub payer: Pubkey,
+    /// Soulbound Token-2022 receipt mint
+    pub mint: Pubkey,
     /// Agent executed
     pub agent_id: String,
     /// Amount paid
@@ -26,11 +28,14 @@ pub struct Receipt {
 impl Receipt {
     pub const MAX_AGENT_ID_LEN: usize = 32;
     pub const MAX_RESULT_CID_LEN: usize = 64;
+    pub const NFT_NAME: &'static str = "SynapsePay Receipt";
+    pub const NFT_SYMBOL: &'static str = "SPRCPT";
     
     pub const LEN: usize = 8 + // discriminator
         32 + // receipt_id
         32 + // payment
         32 + // payer
+        32 + // mint
         4 + Self::MAX_AGENT_ID_LEN + // agent_id
         8 + // amount
         4 + Self::MAX_RESULT_CID_LEN + // result_cid
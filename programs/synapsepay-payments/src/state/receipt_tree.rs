@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// Append-only Merkle accumulator of compressed receipts.
+/// Keeps only the rightmost path of the tree plus a changelog of recent roots,
+/// so proofs built against a slightly stale root still verify after later appends.
+#[account]
+pub struct ReceiptTree {
+    /// Number of leaves appended so far (index of the next leaf)
+    pub next_index: u64,
+    /// Current root
+    pub root: [u8; 32],
+    /// Last left node seen at each level, used to extend the tree on append
+    pub filled_subtrees: [[u8; 32]; ReceiptTree::DEPTH],
+    /// Ring buffer of recent roots
+    pub changelog: [[u8; 32]; ReceiptTree::CHANGELOG_LEN],
+    /// Position of the current root in the changelog
+    pub changelog_index: u32,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl ReceiptTree {
+    /// Tree depth - 2^20 receipts
+    pub const DEPTH: usize = 20;
+    /// Number of recent roots proofs may be checked against
+    pub const CHANGELOG_LEN: usize = 32;
+
+    pub const LEN: usize = 8 + // discriminator
+        8 + // next_index
+        32 + // root
+        32 * Self::DEPTH + // filled_subtrees
+        32 * Self::CHANGELOG_LEN + // changelog
+        4 + // changelog_index
+        1; // bump
+
+    /// Reset to an empty tree
+    pub fn initialize(&mut self, bump: u8) {
+        let mut zero = [0u8; 32];
+        for level in 0..Self::DEPTH {
+            self.filled_subtrees[level] = zero;
+            zero = hash_nodes(&zero, &zero);
+        }
+        self.next_index = 0;
+        self.root = zero;
+        self.changelog = [[0u8; 32]; Self::CHANGELOG_LEN];
+        self.changelog[0] = zero;
+        self.changelog_index = 0;
+        self.bump = bump;
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.next_index >= 1u64 << Self::DEPTH
+    }
+
+    /// Append a leaf and return its index. Callers check `is_full` first.
+    pub fn append(&mut self, leaf: [u8; 32]) -> u64 {
+        let index = self.next_index;
+        let mut node = leaf;
+        let mut zero = [0u8; 32];
+        for level in 0..Self::DEPTH {
+            if (index >> level) & 1 == 0 {
+                // Left child: remember it and pair with an empty right subtree
+                self.filled_subtrees[level] = node;
+                node = hash_nodes(&node, &zero);
+            } else {
+                node = hash_nodes(&self.filled_subtrees[level], &node);
+            }
+            zero = hash_nodes(&zero, &zero);
+        }
+
+        self.next_index = index + 1;
+        self.root = node;
+        self.changelog_index = (self.changelog_index + 1) % Self::CHANGELOG_LEN as u32;
+        self.changelog[self.changelog_index as usize] = node;
+        index
+    }
+
+    /// Check that `leaf` sits at `index` under the current root or one in the changelog
+    pub fn verify(&self, leaf: [u8; 32], index: u64, proof: &[[u8; 32]]) -> bool {
+        if proof.len() != Self::DEPTH || index >= self.next_index {
+            return false;
+        }
+
+        let mut node = leaf;
+        for (level, sibling) in proof.iter().enumerate() {
+            node = if (index >> level) & 1 == 0 {
+                hash_nodes(&node, sibling)
+            } else {
+                hash_nodes(sibling, &node)
+            };
+        }
+
+        self.changelog.contains(&node)
+    }
+}
+
+fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[left, right]).to_bytes()
+}
+
+/// Receipt data committed to by a compressed receipt leaf
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReceiptLeaf {
+    /// Payment PDA
+    pub payment: Pubkey,
+    /// User wallet
+    pub payer: Pubkey,
+    /// Agent executed
+    pub agent_id: String,
+    /// Amount paid
+    pub amount: u64,
+    /// Result IPFS CID
+    pub result_cid: String,
+    /// Slot the receipt was recorded in
+    pub slot: u64,
+}
+
+impl ReceiptLeaf {
+    /// Domain separator so a leaf can never be mistaken for an inner node
+    pub const PREFIX: &'static [u8] = b"synapsepay:receipt-leaf:v1";
+
+    /// Leaf hash: prefix, payment, payer, length-prefixed agent_id,
+    /// amount LE, length-prefixed result_cid, slot LE
+    pub fn hash(&self) -> [u8; 32] {
+        hashv(&[
+            Self::PREFIX,
+            self.payment.as_ref(),
+            self.payer.as_ref(),
+            &(self.agent_id.len() as u32).to_le_bytes(),
+            self.agent_id.as_bytes(),
+            &self.amount.to_le_bytes(),
+            &(self.result_cid.len() as u32).to_le_bytes(),
+            self.result_cid.as_bytes(),
+            &self.slot.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+}
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+/// Platform fee share owed to a referrer in one mint
+#[account]
+#[derive(Default)]
+pub struct ReferralAccrual {
+    /// Referrer wallet
+    pub referrer: Pubkey,
+    /// Token mint
+    pub mint: Pubkey,
+    /// Credit not yet withdrawn, held in the mint's fee treasury
+    pub accrued: u64,
+    /// Lifetime credit
+    pub total_earned: u64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl ReferralAccrual {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // referrer
+        32 + // mint
+        8 + // accrued
+        8 + // total_earned
+        1; // bump
+}
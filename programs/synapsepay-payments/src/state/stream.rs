@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+/// Per-second payment stream from a payer to one agent.
+/// Tokens accrue to the recipient at `rate_per_second` until the deposit runs out.
+#[account]
+#[derive(Default)]
+pub struct Stream {
+    /// PDA derived ID
+    pub stream_id: Pubkey,
+    /// User wallet
+    pub payer: Pubkey,
+    /// Agent owner
+    pub recipient: Pubkey,
+    /// Registry agent account
+    pub agent: Pubkey,
+    /// Payment token mint
+    pub mint: Pubkey,
+    /// Client-chosen nonce, shared with invoices and channels
+    pub nonce: u64,
+    /// Total deposited into the stream escrow
+    pub deposit: u64,
+    /// Amount accrued per second, in mint base units
+    pub rate_per_second: u64,
+    /// Total already withdrawn by the recipient (fees included)
+    pub withdrawn: u64,
+    /// Platform fees taken from withdrawals
+    pub platform_fee: u64,
+    /// Platform fee rate locked in when the stream was created
+    pub fee_bps: u16,
+    /// Platform minimum fee locked in when the stream was created
+    pub min_fee: u64,
+    /// When accrual started
+    pub start_time: i64,
+    /// Bump seed
+    pub bump: u8,
+}
+
+impl Stream {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // stream_id
+        32 + // payer
+        32 + // recipient
+        32 + // agent
+        32 + // mint
+        8 + // nonce
+        8 + // deposit
+        8 + // rate_per_second
+        8 + // withdrawn
+        8 + // platform_fee
+        2 + // fee_bps
+        8 + // min_fee
+        8 + // start_time
+        1; // bump
+
+    /// Total accrued to the recipient at `now`, capped by the deposit
+    pub fn accrued_at(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.start_time).max(0) as u64;
+        elapsed.saturating_mul(self.rate_per_second).min(self.deposit)
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use crate::state::Agent;
+use super::register_agent::RegistryError;
+
+#[derive(Accounts)]
+pub struct MigrateAgent<'info> {
+    /// Pays the rent for the added space
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Agent registered before the current layout
+    /// CHECK: Too short to deserialize until migrated; discriminator is checked below
+    #[account(
+        mut,
+        owner = crate::ID @ RegistryError::InvalidAgentAccount
+    )]
+    pub agent: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateAgent>) -> Result<()> {
+    let agent_info = ctx.accounts.agent.to_account_info();
+
+    require!(agent_info.data_len() < Agent::LEN, RegistryError::AgentAlreadyMigrated);
+    {
+        let data = agent_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Agent::DISCRIMINATOR,
+            RegistryError::InvalidAgentAccount
+        );
+    }
+
+    // Fund the larger account, then grow it; the new bytes are zeroed
+    let rent_due = Rent::get()?
+        .minimum_balance(Agent::LEN)
+        .saturating_sub(agent_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: agent_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    agent_info.realloc(Agent::LEN, true)?;
+
+    // Appended fields read back as their zero defaults, except the SLA,
+    // which newly registered agents start with
+    let mut agent = Agent::try_deserialize(&mut &agent_info.try_borrow_data()?[..])?;
+    agent.sla_seconds = Agent::DEFAULT_SLA_SECONDS;
+    agent.updated_at = Clock::get()?.unix_timestamp;
+    agent.try_serialize(&mut &mut agent_info.try_borrow_mut_data()?[..])?;
+
+    msg!("Agent migrated: {}", agent.agent_id);
+    Ok(())
+}
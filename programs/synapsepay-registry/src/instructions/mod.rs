@@ -3,9 +3,17 @@ pub mod update_agent;
 pub mod deactivate_agent;
 pub mod reactivate_agent;
 pub mod transfer_ownership;
+pub mod set_accepted_mints;
+pub mod set_usage_pricing;
+pub mod set_payees;
+pub mod migrate_agent;
 
 pub use register_agent::*;
 pub use update_agent::*;
 pub use deactivate_agent::*;
 pub use reactivate_agent::*;
 pub use transfer_ownership::*;
+pub use set_accepted_mints::*;
+pub use set_usage_pricing::*;
+pub use set_payees::*;
+pub use migrate_agent::*;
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{AgentCategory, state::Agent};
+use crate::{AgentCategory, UsageUnit, state::Agent};
 
 #[derive(Accounts)]
 #[instruction(agent_id: String)]
@@ -37,6 +37,13 @@ pub fn handler(
     agent.agent_id = agent_id;
     agent.metadata_cid = metadata_cid;
     agent.price = price;
+    agent.accepted_mints = Vec::new();
+    agent.challenge_window = 0;
+    agent.sla_seconds = Agent::DEFAULT_SLA_SECONDS;
+    agent.executor = Pubkey::default();
+    agent.usage_unit = UsageUnit::default();
+    agent.unit_price = 0;
+    agent.payees = Vec::new();
     agent.category = category;
     agent.total_runs = 0;
     agent.total_earned = 0;
@@ -63,4 +70,18 @@ pub enum RegistryError {
     Unauthorized,
     #[msg("Agent is not active")]
     AgentNotActive,
+    #[msg("Too many accepted mints")]
+    TooManyMints,
+    #[msg("Invalid challenge window")]
+    InvalidChallengeWindow,
+    #[msg("Invalid SLA")]
+    InvalidSla,
+    #[msg("Too many payees")]
+    TooManyPayees,
+    #[msg("Payee shares must be non-zero, unique and total at most 10000 bps")]
+    InvalidPayeeShares,
+    #[msg("Agent account already has the current layout")]
+    AgentAlreadyMigrated,
+    #[msg("Account is not a registry agent")]
+    InvalidAgentAccount,
 }
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::state::Agent;
+use super::register_agent::RegistryError;
+
+#[derive(Accounts)]
+pub struct SetAcceptedMints<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ RegistryError::Unauthorized,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+}
+
+pub fn handler(ctx: Context<SetAcceptedMints>, mints: Vec<Pubkey>) -> Result<()> {
+    require!(mints.len() <= Agent::MAX_ACCEPTED_MINTS, RegistryError::TooManyMints);
+
+    let agent = &mut ctx.accounts.agent;
+    let clock = Clock::get()?;
+
+    agent.accepted_mints = mints;
+    agent.updated_at = clock.unix_timestamp;
+
+    msg!("Agent accepted mints updated: {} ({} mints)", agent.agent_id, agent.accepted_mints.len());
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{Payee, state::Agent};
+use super::register_agent::RegistryError;
+
+#[derive(Accounts)]
+pub struct SetPayees<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ RegistryError::Unauthorized,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+}
+
+pub fn handler(ctx: Context<SetPayees>, payees: Vec<Payee>) -> Result<()> {
+    require!(payees.len() <= Agent::MAX_PAYEES, RegistryError::TooManyPayees);
+
+    let mut total_bps: u64 = 0;
+    for (i, payee) in payees.iter().enumerate() {
+        require!(payee.share_bps > 0, RegistryError::InvalidPayeeShares);
+        require!(
+            !payees[..i].iter().any(|other| other.wallet == payee.wallet),
+            RegistryError::InvalidPayeeShares
+        );
+        total_bps += payee.share_bps as u64;
+    }
+    require!(total_bps <= Payee::BPS_DENOMINATOR, RegistryError::InvalidPayeeShares);
+
+    let agent = &mut ctx.accounts.agent;
+    let clock = Clock::get()?;
+
+    agent.payees = payees;
+    agent.updated_at = clock.unix_timestamp;
+
+    msg!("Agent payees updated: {} ({} payees, {} bps)", agent.agent_id, agent.payees.len(), total_bps);
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::{UsageUnit, state::Agent};
+use super::register_agent::RegistryError;
+
+#[derive(Accounts)]
+pub struct SetUsagePricing<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = owner @ RegistryError::Unauthorized,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+}
+
+pub fn handler(
+    ctx: Context<SetUsagePricing>,
+    executor: Pubkey,
+    unit: UsageUnit,
+    unit_price: u64,
+) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let clock = Clock::get()?;
+
+    agent.executor = executor;
+    agent.usage_unit = unit;
+    agent.unit_price = unit_price;
+    agent.updated_at = clock.unix_timestamp;
+
+    msg!("Agent usage pricing updated: {} - {} per {:?}", agent.agent_id, unit_price, unit);
+    Ok(())
+}
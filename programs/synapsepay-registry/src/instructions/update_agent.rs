@@ -20,6 +20,8 @@ pub fn handler(
     ctx: Context<UpdateAgent>,
     new_metadata_cid: Option<String>,
     new_price: Option<u64>,
+    new_challenge_window: Option<i64>,
+    new_sla_seconds: Option<i64>,
 ) -> Result<()> {
     let agent = &mut ctx.accounts.agent;
     let clock = Clock::get()?;
@@ -34,6 +36,16 @@ pub fn handler(
         agent.price = price;
     }
 
+    if let Some(challenge_window) = new_challenge_window {
+        require!(challenge_window >= 0, RegistryError::InvalidChallengeWindow);
+        agent.challenge_window = challenge_window;
+    }
+
+    if let Some(sla_seconds) = new_sla_seconds {
+        require!(sla_seconds > 0, RegistryError::InvalidSla);
+        agent.sla_seconds = sla_seconds;
+    }
+
     agent.updated_at = clock.unix_timestamp;
 
     msg!("Agent updated: {}", agent.agent_id);
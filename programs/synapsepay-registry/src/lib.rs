@@ -22,13 +22,21 @@ pub mod synapsepay_registry {
         instructions::register_agent::handler(ctx, agent_id, metadata_cid, price, category)
     }
 
-    /// Update an existing agent's metadata and price
+    /// Update an existing agent's metadata, price, challenge window and SLA
     pub fn update_agent(
         ctx: Context<UpdateAgent>,
         new_metadata_cid: Option<String>,
         new_price: Option<u64>,
+        new_challenge_window: Option<i64>,
+        new_sla_seconds: Option<i64>,
     ) -> Result<()> {
-        instructions::update_agent::handler(ctx, new_metadata_cid, new_price)
+        instructions::update_agent::handler(
+            ctx,
+            new_metadata_cid,
+            new_price,
+            new_challenge_window,
+            new_sla_seconds,
+        )
     }
 
     /// Deactivate an agent (remove from marketplace)
@@ -45,6 +53,31 @@ pub mod synapsepay_registry {
     pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
         instructions::transfer_ownership::handler(ctx, new_owner)
     }
+
+    /// Set the token mints an agent accepts payment in
+    pub fn set_accepted_mints(ctx: Context<SetAcceptedMints>, mints: Vec<Pubkey>) -> Result<()> {
+        instructions::set_accepted_mints::handler(ctx, mints)
+    }
+
+    /// Set the executor key and per-unit price used for metered billing
+    pub fn set_usage_pricing(
+        ctx: Context<SetUsagePricing>,
+        executor: Pubkey,
+        unit: UsageUnit,
+        unit_price: u64,
+    ) -> Result<()> {
+        instructions::set_usage_pricing::handler(ctx, executor, unit, unit_price)
+    }
+
+    /// Set the third parties that receive a share of every claimed payment
+    pub fn set_payees(ctx: Context<SetPayees>, payees: Vec<Payee>) -> Result<()> {
+        instructions::set_payees::handler(ctx, payees)
+    }
+
+    /// Grow an agent registered before the current layout to `Agent::LEN`
+    pub fn migrate_agent(ctx: Context<MigrateAgent>) -> Result<()> {
+        instructions::migrate_agent::handler(ctx)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -62,3 +95,32 @@ impl Default for AgentCategory {
         AgentCategory::AI
     }
 }
+
+/// Unit a metered agent bills by
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum UsageUnit {
+    #[default]
+    Call,
+    Tokens1k,
+    Second,
+    Page,
+}
+
+/// Revenue share paid to a third party on claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Payee {
+    /// Wallet whose token account receives the share
+    pub wallet: Pubkey,
+    /// Share of the claimed amount in basis points
+    pub share_bps: u16,
+}
+
+impl Payee {
+    pub const BPS_DENOMINATOR: u64 = 10_000;
+    pub const LEN: usize = 32 + 2;
+
+    /// This payee's cut of `amount`, rounded down
+    pub fn share_of(&self, amount: u64) -> u64 {
+        (amount as u128 * self.share_bps as u128 / Self::BPS_DENOMINATOR as u128) as u64
+    }
+}
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::AgentCategory;
+use crate::{AgentCategory, Payee, UsageUnit};
 
 #[account]
 #[derive(Default)]
@@ -30,11 +30,31 @@ pub struct Agent {
     pub updated_at: i64,
     /// Bump seed for PDA
     pub bump: u8,
+    // Fields below were added after launch; `migrate_agent` extends older accounts
+    /// Mints the agent accepts (empty = any platform mint)
+    pub accepted_mints: Vec<Pubkey>,
+    /// Seconds the payer has to challenge a result (0 = platform default)
+    pub challenge_window: i64,
+    /// Seconds the agent commits to finish a paid task within
+    pub sla_seconds: i64,
+    /// Key that signs usage reports (default = metering disabled)
+    pub executor: Pubkey,
+    /// Unit usage is billed by
+    pub usage_unit: UsageUnit,
+    /// Price per usage unit (0 = flat pricing only)
+    pub unit_price: u64,
+    /// Third parties paid a share of each claim; the owner keeps the rest
+    pub payees: Vec<Payee>,
 }
 
 impl Agent {
     pub const MAX_AGENT_ID_LEN: usize = 32;
     pub const MAX_METADATA_CID_LEN: usize = 64;
+    pub const MAX_ACCEPTED_MINTS: usize = 5;
+    pub const DEFAULT_SLA_SECONDS: i64 = 3600;
+    pub const MAX_PAYEES: usize = 5;
+    /// Decimals `price` is denominated in (USDC)
+    pub const PRICE_DECIMALS: u8 = 6;
     
     pub const LEN: usize = 8 + // discriminator
         32 + // owner
@@ -49,5 +69,16 @@ impl Agent {
         1 + // is_active
         8 + // created_at
         8 + // updated_at
-        1; // bump
+        1 + // bump
+        4 + 32 * Self::MAX_ACCEPTED_MINTS + // accepted_mints
+        8 + // challenge_window
+        8 + // sla_seconds
+        32 + // executor
+        1 + // usage_unit
+        8 + // unit_price
+        4 + Payee::LEN * Self::MAX_PAYEES; // payees
+
+    pub fn accepts_mint(&self, mint: &Pubkey) -> bool {
+        self.accepted_mints.is_empty() || self.accepted_mints.contains(mint)
+    }
 }
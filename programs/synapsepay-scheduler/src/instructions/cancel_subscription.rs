@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 use crate::state::Subscription;
 use super::create_subscription::SchedulerError;
 
@@ -15,17 +15,28 @@ pub struct CancelSubscription<'info> {
     )]
     pub subscription: Account<'info, Subscription>,
 
-    /// Owner's USDC token account (for refund)
-    #[account(mut)]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    /// Mint held by the subscription vault
+    #[account(
+        address = subscription_vault.mint @ SchedulerError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Owner's token account (for refund)
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == mint.key() @ SchedulerError::InvalidMint,
+        constraint = owner_token_account.owner == owner.key() @ SchedulerError::InvalidTokenOwner,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
 
     /// Subscription's token vault
     #[account(
         mut,
         seeds = [b"subscription_vault", subscription.key().as_ref()],
         bump,
+        constraint = subscription_vault.owner == vault_authority.key() @ SchedulerError::InvalidVaultAuthority,
     )]
-    pub subscription_vault: Account<'info, TokenAccount>,
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
 
     /// Subscription vault authority PDA
     /// CHECK: PDA signer for subscription vault
@@ -35,7 +46,7 @@ pub struct CancelSubscription<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<CancelSubscription>) -> Result<()> {
@@ -50,8 +61,9 @@ pub fn handler(ctx: Context<CancelSubscription>) -> Result<()> {
         ];
         let signer_seeds = &[&seeds[..]];
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.subscription_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.owner_token_account.to_account_info(),
             authority: ctx.accounts.vault_authority.to_account_info(),
         };
@@ -59,7 +71,7 @@ pub fn handler(ctx: Context<CancelSubscription>) -> Result<()> {
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
 
-        token::transfer(cpi_ctx, remaining_balance)?;
+        token_interface::transfer_checked(cpi_ctx, remaining_balance, ctx.accounts.mint.decimals)?;
 
         msg!("Refunded {} USDC to owner", remaining_balance);
     }
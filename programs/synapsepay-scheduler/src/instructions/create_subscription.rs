@@ -66,4 +66,16 @@ pub enum SchedulerError {
     NotTimeYet,
     #[msg("Max runs reached")]
     MaxRunsReached,
+    #[msg("Fee treasury does not match platform config")]
+    InvalidFeeTreasury,
+    #[msg("Mint is not accepted by the platform")]
+    InvalidMint,
+    #[msg("Token account is not owned by the expected wallet")]
+    InvalidTokenOwner,
+    #[msg("Subscription vault is not owned by the vault authority")]
+    InvalidVaultAuthority,
+    #[msg("Payment escrow is not owned by the payments escrow authority")]
+    InvalidPaymentEscrow,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
 }
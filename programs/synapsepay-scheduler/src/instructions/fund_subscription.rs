@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use synapsepay_payments::{escrow::transfer_received, state::AcceptedMint};
 use crate::state::Subscription;
 use super::create_subscription::SchedulerError;
 
@@ -14,19 +15,50 @@ pub struct FundSubscription<'info> {
     )]
     pub subscription: Account<'info, Subscription>,
 
-    /// Owner's USDC token account
-    #[account(mut)]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    /// Subscription payment mint
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payments program accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump,
+        seeds::program = synapsepay_payments::ID,
+        constraint = accepted_mint.is_active @ SchedulerError::InvalidMint,
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
 
-    /// Subscription's token vault (holds pre-funded USDC)
+    /// Owner's token account
     #[account(
         mut,
+        constraint = owner_token_account.mint == mint.key() @ SchedulerError::InvalidMint,
+        constraint = owner_token_account.owner == owner.key() @ SchedulerError::InvalidTokenOwner,
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Subscription's token vault (holds pre-funded USDC), created on first funding
+    #[account(
+        init_if_needed,
+        payer = owner,
         seeds = [b"subscription_vault", subscription.key().as_ref()],
         bump,
+        token::mint = mint,
+        token::authority = vault_authority,
+        token::token_program = token_program,
+        constraint = subscription_vault.mint == mint.key() @ SchedulerError::InvalidMint,
+        constraint = subscription_vault.owner == vault_authority.key() @ SchedulerError::InvalidVaultAuthority,
     )]
-    pub subscription_vault: Account<'info, TokenAccount>,
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Subscription vault authority PDA
+    /// CHECK: Expected owner of the subscription vault
+    #[account(
+        seeds = [b"subscription_vault_authority"],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<FundSubscription>, amount: u64) -> Result<()> {
@@ -35,23 +67,23 @@ pub fn handler(ctx: Context<FundSubscription>, amount: u64) -> Result<()> {
     require!(amount > 0, SchedulerError::InsufficientBalance);
 
     // Transfer USDC from owner to subscription vault
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.owner_token_account.to_account_info(),
-        to: ctx.accounts.subscription_vault.to_account_info(),
-        authority: ctx.accounts.owner.to_account_info(),
-    };
-
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-
-    token::transfer(cpi_ctx, amount)?;
+    let received = transfer_received(
+        ctx.accounts.owner_token_account.to_account_info(),
+        &mut ctx.accounts.subscription_vault,
+        &ctx.accounts.mint,
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
 
     // Update subscription balance
-    subscription.balance += amount;
+    subscription.balance = subscription.balance
+        .checked_add(received)
+        .ok_or(SchedulerError::MathOverflow)?;
 
     msg!("Subscription funded: {} - added {} USDC (new balance: {})",
         subscription.subscription_id,
-        amount,
+        received,
         subscription.balance
     );
 
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use synapsepay_payments::state::{AcceptedMint, PlatformConfig};
+use synapsepay_registry::state::Agent;
 use crate::state::Subscription;
 use super::create_subscription::SchedulerError;
 
@@ -15,30 +17,70 @@ pub struct TriggerScheduledTask<'info> {
     )]
     pub subscription: Account<'info, Subscription>,
 
-    /// Agent account to get pricing
-    /// CHECK: Agent registry account
-    #[account()]
-    pub agent: UncheckedAccount<'info>,
+    /// Registry agent the subscription pays, priced in USDC base units
+    #[account(
+        seeds = [b"agent", subscription.agent_id.as_bytes()],
+        bump = agent.bump,
+        seeds::program = synapsepay_registry::ID,
+    )]
+    pub agent: Account<'info, Agent>,
+
+    /// Payments program fee configuration
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        seeds::program = synapsepay_payments::ID,
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+
+    /// Subscription payment mint, with the precision the agent price is quoted in
+    #[account(
+        constraint = mint.decimals == Agent::PRICE_DECIMALS @ SchedulerError::InvalidMint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Payments program accepted-mint entry for `mint`
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump,
+        seeds::program = synapsepay_payments::ID,
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
 
     /// Subscription's token account (holds pre-funded USDC)
     #[account(
         mut,
         seeds = [b"subscription_vault", subscription.key().as_ref()],
         bump,
+        constraint = subscription_vault.mint == mint.key() @ SchedulerError::InvalidMint,
+        constraint = subscription_vault.owner == vault_authority.key() @ SchedulerError::InvalidVaultAuthority,
     )]
-    pub subscription_vault: Account<'info, TokenAccount>,
+    pub subscription_vault: InterfaceAccount<'info, TokenAccount>,
 
     /// Payment escrow for this execution
-    #[account(mut)]
-    pub payment_escrow: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = payment_escrow.mint == mint.key() @ SchedulerError::InvalidMint,
+        constraint = payment_escrow.owner == escrow_authority.key() @ SchedulerError::InvalidPaymentEscrow,
+    )]
+    pub payment_escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Payments program escrow authority PDA
+    /// CHECK: Expected owner of the payment escrow
+    #[account(
+        seeds = [b"escrow_authority"],
+        bump,
+        seeds::program = synapsepay_payments::ID,
+    )]
+    pub escrow_authority: UncheckedAccount<'info>,
 
     /// Platform fee treasury
     #[account(
         mut,
-        seeds = [b"fee_treasury"],
-        bump,
+        constraint = fee_treasury.key() == accepted_mint.fee_treasury @ SchedulerError::InvalidFeeTreasury,
+        constraint = fee_treasury.mint == mint.key() @ SchedulerError::InvalidMint,
     )]
-    pub fee_treasury: Account<'info, TokenAccount>,
+    pub fee_treasury: InterfaceAccount<'info, TokenAccount>,
 
     /// Subscription vault authority PDA
     /// CHECK: PDA signer for subscription vault
@@ -48,7 +90,7 @@ pub struct TriggerScheduledTask<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<TriggerScheduledTask>) -> Result<()> {
@@ -63,14 +105,11 @@ pub fn handler(ctx: Context<TriggerScheduledTask>) -> Result<()> {
         require!(subscription.total_runs < subscription.max_runs, SchedulerError::MaxRunsReached);
     }
 
-    // Get agent price from agent account
-    // For now, we'll use a fixed price or read from agent metadata
-    // In production, this would deserialize the Agent account
-    let agent_price: u64 = 1_000_000; // 1 USDC (6 decimals) - placeholder
-    
-    // Calculate platform fee (5%)
-    let platform_fee = agent_price / 20;
-    let total_cost = agent_price + platform_fee;
+    let agent_price = ctx.accounts.agent.price;
+
+    // Calculate platform fee from the payments program config
+    let platform_fee = ctx.accounts.platform_config.compute_fee(agent_price)?;
+    let total_cost = agent_price.checked_add(platform_fee).ok_or(SchedulerError::MathOverflow)?;
 
     // Check subscription balance
     require!(subscription.balance >= total_cost, SchedulerError::InsufficientBalance);
@@ -85,8 +124,9 @@ pub fn handler(ctx: Context<TriggerScheduledTask>) -> Result<()> {
     ];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts_payment = Transfer {
+    let cpi_accounts_payment = TransferChecked {
         from: ctx.accounts.subscription_vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.payment_escrow.to_account_info(),
         authority: ctx.accounts.vault_authority.to_account_info(),
     };
@@ -94,17 +134,18 @@ pub fn handler(ctx: Context<TriggerScheduledTask>) -> Result<()> {
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx_payment = CpiContext::new_with_signer(cpi_program.clone(), cpi_accounts_payment, signer_seeds);
 
-    token::transfer(cpi_ctx_payment, agent_price)?;
+    token_interface::transfer_checked(cpi_ctx_payment, agent_price, ctx.accounts.mint.decimals)?;
 
     // Transfer platform fee to treasury
-    let cpi_accounts_fee = Transfer {
+    let cpi_accounts_fee = TransferChecked {
         from: ctx.accounts.subscription_vault.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
         to: ctx.accounts.fee_treasury.to_account_info(),
         authority: ctx.accounts.vault_authority.to_account_info(),
     };
 
     let cpi_ctx_fee = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds);
-    token::transfer(cpi_ctx_fee, platform_fee)?;
+    token_interface::transfer_checked(cpi_ctx_fee, platform_fee, ctx.accounts.mint.decimals)?;
 
     // Update subscription
     subscription.last_run_at = clock.unix_timestamp;
@@ -1,21 +1,42 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+    PublicKey,
+    Keypair,
+    SystemProgram,
+    Ed25519Program,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    Transaction,
+    TransactionInstruction,
+    sendAndConfirmTransaction,
+} from "@solana/web3.js";
 import {
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+    getAssociatedTokenAddressSync,
+    getTokenMetadata,
+    ExtensionType,
+    getMintLen,
+    getTransferFeeAmount,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializeMintInstruction,
     createMint,
     createAccount,
     mintTo,
     getAccount,
 } from "@solana/spl-token";
 import { assert } from "chai";
+import { createHash } from "crypto";
 import { SynapsepayPayments } from "../target/types/synapsepay_payments";
+import { SynapsepayRegistry } from "../target/types/synapsepay_registry";
 
 describe("SynapsePay Payments Tests", () => {
     const provider = anchor.AnchorProvider.env();
     anchor.setProvider(provider);
 
     const program = anchor.workspace.SynapsepayPayments as Program<SynapsepayPayments>;
+    const registry = anchor.workspace.SynapsepayRegistry as Program<SynapsepayRegistry>;
 
     const payer = provider.wallet;
     const recipient = Keypair.generate();
@@ -23,16 +44,160 @@ describe("SynapsePay Payments Tests", () => {
     let usdcMint: PublicKey;
     let payerTokenAccount: PublicKey;
     let recipientTokenAccount: PublicKey;
+    let otherMint: PublicKey;
+    let payerOtherMintAccount: PublicKey;
+    let recipientOtherMintAccount: PublicKey;
     let platformAuthority: PublicKey;
     let escrowAuthority: PublicKey;
     let feeTreasury: PublicKey;
+    let acceptedMint: PublicKey;
+    let platformConfig: PublicKey;
 
+    let agentPda: PublicKey;
     let invoicePda: PublicKey;
+    let nonceTracker: PublicKey;
     let paymentPda: PublicKey;
     let escrowPda: PublicKey;
 
-    const testAgentId = "pdf-summarizer";
+    let expiresAt: anchor.BN;
+    let intentIx: TransactionInstruction;
+
+    const testAgentId = "pdf-summarizer-" + Date.now();
     const paymentAmount = new anchor.BN(1_000_000); // 1 USDC
+    const invoiceNonce = new anchor.BN(1);
+
+    const invoiceAddress = (nonce: anchor.BN) =>
+        PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("invoice"),
+                payer.publicKey.toBuffer(),
+                Buffer.from(testAgentId),
+                nonce.toArrayLike(Buffer, "le", 8),
+            ],
+            program.programId
+        );
+
+    // Canonical payment intent: prefix || invoice || amount || recipient || mint || expires_at
+    const paymentIntentMessage = (
+        invoice: PublicKey,
+        amount: anchor.BN,
+        to: PublicKey,
+        mint: PublicKey,
+        expiry: anchor.BN
+    ) =>
+        Buffer.concat([
+            Buffer.from("synapsepay:payment-intent:v1"),
+            invoice.toBuffer(),
+            amount.toArrayLike(Buffer, "le", 8),
+            to.toBuffer(),
+            mint.toBuffer(),
+            expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+        ]);
+
+    const expectError = async (call: Promise<unknown>, code: string) => {
+        try {
+            await call;
+            assert.fail(`Expected ${code}`);
+        } catch (err: any) {
+            assert.include(err.toString(), code);
+        }
+    };
+
+    const [payerStats] = PublicKey.findProgramAddressSync(
+        [Buffer.from("payer_stats"), payer.publicKey.toBuffer()],
+        program.programId
+    );
+
+    const referralAddress = (referrer: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from("referral"), referrer.toBuffer(), usdcMint.toBuffer()],
+            program.programId
+        )[0];
+
+    const integratorAddress = (wallet: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from("integrator"), wallet.toBuffer()],
+            program.programId
+        )[0];
+
+    const escrowAddress = (payment: PublicKey) =>
+        PublicKey.findProgramAddressSync(
+            [Buffer.from("escrow"), payment.toBuffer()],
+            program.programId
+        )[0];
+
+    // Runs create_invoice -> settle_payment -> verify_payment and returns the Executing payment
+    const openExecutingPayment = async (
+        nonce: anchor.BN,
+        expiresIn = 300,
+        mode: object = { fixed: {} },
+        referrer: PublicKey | null = null,
+        withReferralAccrual = true
+    ) => {
+        const [invoice] = invoiceAddress(nonce);
+        const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + expiresIn);
+
+        await program.methods
+            .createInvoice(testAgentId, nonce, paymentAmount, expiry, mode, referrer)
+            .accounts({
+                invoice,
+                nonceTracker,
+                payer: payer.publicKey,
+                agent: agentPda,
+                recipient: recipient.publicKey,
+                mint: usdcMint,
+                acceptedMint,
+                integrator: referrer ? integratorAddress(referrer) : null,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+
+        const ix = Ed25519Program.createInstructionWithPrivateKey({
+            privateKey: payer.payer.secretKey,
+            message: paymentIntentMessage(invoice, paymentAmount, recipient.publicKey, usdcMint, expiry),
+        });
+
+        const [payment] = PublicKey.findProgramAddressSync(
+            [Buffer.from("payment"), invoice.toBuffer()],
+            program.programId
+        );
+        await program.methods
+            .settlePayment(Array.from(ix.data.subarray(48, 112)))
+            .accounts({
+                payer: payer.publicKey,
+                invoice,
+                payment,
+                platformConfig,
+                payerStats,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+
+        const escrow = escrowAddress(payment);
+        await program.methods
+            .verifyPayment()
+            .accounts({
+                payer: payer.publicKey,
+                payment,
+                invoice,
+                mint: usdcMint,
+                acceptedMint,
+                platformConfig,
+                referralAccrual: referrer && withReferralAccrual ? referralAddress(referrer) : null,
+                payerStats,
+                payerTokenAccount,
+                escrowAccount: escrow,
+                escrowAuthority,
+                feeTreasury,
+                instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .preInstructions([ix])
+            .rpc();
+
+        return { invoice, payment, escrow };
+    };
 
     before(async () => {
         console.log("🔧 Setting up Payments test environment...");
@@ -73,6 +238,21 @@ describe("SynapsePay Payments Tests", () => {
         );
         console.log("✓ Minted 10 USDC to payer");
 
+        // A second mint the platform does not accept, for negative tests
+        otherMint = await createMint(provider.connection, payer.payer, payer.publicKey, null, 6);
+        payerOtherMintAccount = await createAccount(
+            provider.connection,
+            payer.payer,
+            otherMint,
+            payer.publicKey
+        );
+        recipientOtherMintAccount = await createAccount(
+            provider.connection,
+            payer.payer,
+            otherMint,
+            recipient.publicKey
+        );
+
         // Derive PDAs
         [platformAuthority] = PublicKey.findProgramAddressSync(
             [Buffer.from("platform_authority")],
@@ -84,11 +264,42 @@ describe("SynapsePay Payments Tests", () => {
             program.programId
         );
 
+        [acceptedMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("accepted_mint"), usdcMint.toBuffer()],
+            program.programId
+        );
+
         [feeTreasury] = PublicKey.findProgramAddressSync(
-            [Buffer.from("fee_treasury")],
+            [Buffer.from("fee_treasury"), usdcMint.toBuffer()],
             program.programId
         );
 
+        [platformConfig] = PublicKey.findProgramAddressSync(
+            [Buffer.from("platform_config")],
+            program.programId
+        );
+
+        // Register the agent in the registry and hand it to the recipient
+        [agentPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("agent"), Buffer.from(testAgentId)],
+            registry.programId
+        );
+
+        await registry.methods
+            .registerAgent(testAgentId, "QmAgentMetadata", paymentAmount, { ai: {} })
+            .accounts({
+                agent: agentPda,
+                owner: payer.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+
+        await registry.methods
+            .transferOwnership(recipient.publicKey)
+            .accounts({ owner: payer.publicKey, agent: agentPda })
+            .rpc();
+        console.log("✓ Agent registered:", agentPda.toBase58());
+
         console.log("✓ Platform Authority:", platformAuthority.toBase58());
         console.log("✓ Escrow Authority:", escrowAuthority.toBase58());
         console.log("✓ Fee Treasury:", feeTreasury.toBase58());
@@ -99,13 +310,15 @@ describe("SynapsePay Payments Tests", () => {
             console.log("\n📝 Test: Initialize Platform");
 
             const tx = await program.methods
-                .initializePlatform()
+                .initializePlatform(500, new anchor.BN(0)) // 5%, no minimum
                 .accounts({
                     admin: payer.publicKey,
                     platformAuthority,
                     escrowAuthority,
                     usdcMint,
+                    acceptedMint,
                     feeTreasury,
+                    platformConfig,
                     tokenProgram: TOKEN_PROGRAM_ID,
                     systemProgram: SystemProgram.programId,
                 })
@@ -122,34 +335,261 @@ describe("SynapsePay Payments Tests", () => {
             assert.equal(treasuryAccount.mint.toBase58(), usdcMint.toBase58());
             assert.equal(treasuryAccount.amount.toString(), "0");
 
+            const configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.equal(configAccount.feeBps, 500);
+
+            const mintEntry = await program.account.acceptedMint.fetch(acceptedMint);
+            assert.equal(mintEntry.mint.toBase58(), usdcMint.toBase58());
+            assert.equal(mintEntry.feeTreasury.toBase58(), feeTreasury.toBase58());
+            assert.isTrue(mintEntry.isActive);
+
             console.log("✓ Platform initialized successfully");
             console.log("  - Fee Treasury:", feeTreasury.toBase58());
         });
+
+        it("✅ Should update platform fee config and reject invalid bps", async () => {
+            console.log("\n📝 Test: Update Platform Config");
+
+            try {
+                await program.methods
+                    .updatePlatformConfig(10_001, null, null, null, null)
+                    .accounts({ admin: payer.publicKey, platformConfig })
+                    .rpc();
+                assert.fail("fee_bps above 10000 should be rejected");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidFeeBps");
+            }
+
+            await program.methods
+                .updatePlatformConfig(500, new anchor.BN(1_000), null, null, null)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            const configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.equal(configAccount.feeBps, 500);
+            assert.equal(configAccount.minFee.toString(), "1000");
+
+            console.log("✓ Platform config updated");
+        });
+    });
+
+    describe("1b. Platform Roles", () => {
+        it("✅ Should grant facilitator and refund operator roles", async () => {
+            console.log("\n📝 Test: Grant Roles");
+
+            await program.methods
+                .grantRole({ facilitator: {} }, payer.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            await program.methods
+                .grantRole({ refundOperator: {} }, payer.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            await program.methods
+                .grantRole({ arbiter: {} }, payer.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            const configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.equal(configAccount.facilitators[0].toBase58(), payer.publicKey.toBase58());
+            assert.equal(configAccount.refundOperators[0].toBase58(), payer.publicKey.toBase58());
+            assert.equal(configAccount.arbiters[0].toBase58(), payer.publicKey.toBase58());
+
+            console.log("✓ Roles granted to:", payer.publicKey.toBase58());
+        });
+
+        it("✅ Should let only the admin revoke a role", async () => {
+            const revokeAs = (admin: Keypair) =>
+                program.methods
+                    .revokeRole({ refundOperator: {} }, recipient.publicKey)
+                    .accounts({ admin: admin.publicKey, platformConfig })
+                    .signers([admin])
+                    .rpc();
+
+            await program.methods
+                .grantRole({ refundOperator: {} }, recipient.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            await expectError(revokeAs(recipient), "NotAdmin");
+
+            await revokeAs(payer.payer);
+            await expectError(revokeAs(payer.payer), "RoleNotGranted");
+
+            const configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.deepEqual(
+                configAccount.refundOperators.map((key) => key.toBase58()),
+                [payer.publicKey.toBase58()]
+            );
+        });
+
+        it("✅ Should hand over admin in two steps", async () => {
+            console.log("\n📝 Test: Admin Handover");
+
+            const newAdmin = Keypair.generate();
+
+            await program.methods
+                .proposeAdmin(newAdmin.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            // Only the proposed key can accept
+            try {
+                await program.methods
+                    .acceptAdmin()
+                    .accounts({ newAdmin: recipient.publicKey, platformConfig })
+                    .signers([recipient])
+                    .rpc();
+                assert.fail("Only the pending admin may accept");
+            } catch (err: any) {
+                assert.include(err.toString(), "NotPendingAdmin");
+            }
+
+            await program.methods
+                .acceptAdmin()
+                .accounts({ newAdmin: newAdmin.publicKey, platformConfig })
+                .signers([newAdmin])
+                .rpc();
+
+            let configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.equal(configAccount.admin.toBase58(), newAdmin.publicKey.toBase58());
+
+            // Hand admin back for the remaining tests
+            await program.methods
+                .proposeAdmin(payer.publicKey)
+                .accounts({ admin: newAdmin.publicKey, platformConfig })
+                .signers([newAdmin])
+                .rpc();
+            await program.methods
+                .acceptAdmin()
+                .accounts({ newAdmin: payer.publicKey, platformConfig })
+                .rpc();
+
+            configAccount = await program.account.platformConfig.fetch(platformConfig);
+            assert.equal(configAccount.admin.toBase58(), payer.publicKey.toBase58());
+            assert.isNull(configAccount.pendingAdmin);
+
+            console.log("✓ Admin handover completed");
+        });
+    });
+
+    describe("1c. Accepted Mints", () => {
+        it("✅ Should add a second mint with its own fee treasury", async () => {
+            console.log("\n📝 Test: Add Accepted Mint");
+
+            const [otherAcceptedMint] = PublicKey.findProgramAddressSync(
+                [Buffer.from("accepted_mint"), otherMint.toBuffer()],
+                program.programId
+            );
+            const [otherFeeTreasury] = PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_treasury"), otherMint.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .addAcceptedMint()
+                .accounts({
+                    admin: payer.publicKey,
+                    platformConfig,
+                    platformAuthority,
+                    mint: otherMint,
+                    acceptedMint: otherAcceptedMint,
+                    feeTreasury: otherFeeTreasury,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const mintEntry = await program.account.acceptedMint.fetch(otherAcceptedMint);
+            assert.equal(mintEntry.feeTreasury.toBase58(), otherFeeTreasury.toBase58());
+            assert.isTrue(mintEntry.isActive);
+
+            const treasuryAccount = await getAccount(provider.connection, otherFeeTreasury);
+            assert.equal(treasuryAccount.mint.toBase58(), otherMint.toBase58());
+
+            console.log("✓ Second mint accepted:", otherMint.toBase58());
+        });
+
+        it("❌ Should reject invoices in a disabled or agent-excluded mint", async () => {
+            const [otherAcceptedMint] = PublicKey.findProgramAddressSync(
+                [Buffer.from("accepted_mint"), otherMint.toBuffer()],
+                program.programId
+            );
+            const [tracker] = PublicKey.findProgramAddressSync(
+                [Buffer.from("nonce_tracker"), payer.publicKey.toBuffer()],
+                program.programId
+            );
+            const nonce = invoiceNonce.addn(300);
+            const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 300);
+
+            const createInOtherMint = () =>
+                program.methods
+                    .createInvoice(testAgentId, nonce, paymentAmount, expiry, { fixed: {} }, null)
+                    .accounts({
+                        invoice: invoiceAddress(nonce)[0],
+                        nonceTracker: tracker,
+                        payer: payer.publicKey,
+                        agent: agentPda,
+                        recipient: recipient.publicKey,
+                        mint: otherMint,
+                        acceptedMint: otherAcceptedMint,
+                        integrator: null,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+
+            await program.methods
+                .updateAcceptedMint(false)
+                .accounts({ admin: payer.publicKey, platformConfig, acceptedMint: otherAcceptedMint })
+                .rpc();
+            await expectError(createInOtherMint(), "MintNotAccepted");
+
+            await program.methods
+                .updateAcceptedMint(true)
+                .accounts({ admin: payer.publicKey, platformConfig, acceptedMint: otherAcceptedMint })
+                .rpc();
+
+            // The agent owner restricts the agent to USDC only
+            await registry.methods
+                .setAcceptedMints([usdcMint])
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+            await expectError(createInOtherMint(), "AgentMintNotAccepted");
+
+            const agentAccount = await registry.account.agent.fetch(agentPda);
+            assert.deepEqual(
+                agentAccount.acceptedMints.map((m: PublicKey) => m.toBase58()),
+                [usdcMint.toBase58()]
+            );
+        });
     });
 
     describe("2. Create Invoice", () => {
         it("✅ Should create payment invoice", async () => {
             console.log("\n📝 Test: Create Invoice");
 
-            const timestamp = Date.now();
-            const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 300); // 5 minutes
+            expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 300); // 5 minutes
 
-            [invoicePda] = PublicKey.findProgramAddressSync(
-                [
-                    Buffer.from("invoice"),
-                    payer.publicKey.toBuffer(),
-                    Buffer.from(testAgentId),
-                    Buffer.from(timestamp.toString()),
-                ],
+            [invoicePda] = invoiceAddress(invoiceNonce);
+            [nonceTracker] = PublicKey.findProgramAddressSync(
+                [Buffer.from("nonce_tracker"), payer.publicKey.toBuffer()],
                 program.programId
             );
 
             const tx = await program.methods
-                .createInvoice(testAgentId, paymentAmount, expiresAt)
+                .createInvoice(testAgentId, invoiceNonce, paymentAmount, expiresAt, { fixed: {} }, null)
                 .accounts({
                     invoice: invoicePda,
+                    nonceTracker,
                     payer: payer.publicKey,
+                    agent: agentPda,
                     recipient: recipient.publicKey,
+                    mint: usdcMint,
+                    acceptedMint,
+                    integrator: null,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -162,19 +602,89 @@ describe("SynapsePay Payments Tests", () => {
             assert.equal(invoiceAccount.recipient.toBase58(), recipient.publicKey.toBase58());
             assert.equal(invoiceAccount.agentId, testAgentId);
             assert.equal(invoiceAccount.amount.toString(), paymentAmount.toString());
+            assert.equal(invoiceAccount.agent.toBase58(), agentPda.toBase58());
+            assert.equal(invoiceAccount.agentPrice.toString(), paymentAmount.toString());
+            assert.equal(invoiceAccount.mint.toBase58(), usdcMint.toBase58());
+
+            assert.equal(invoiceAccount.nonce.toString(), invoiceNonce.toString());
+
+            const trackerAccount = await program.account.nonceTracker.fetch(nonceTracker);
+            assert.equal(trackerAccount.nextNonce.toString(), invoiceNonce.addn(1).toString());
 
             console.log("✓ Invoice created successfully");
             console.log("  - Invoice ID:", invoiceAccount.invoiceId.toBase58());
             console.log("  - Amount:", invoiceAccount.amount.toString());
             console.log("  - State:", Object.keys(invoiceAccount.state)[0]);
         });
+
+        it("❌ Should reject a nonce below the payer's high-water mark", async () => {
+            const staleNonce = new anchor.BN(0);
+            const [staleInvoice] = invoiceAddress(staleNonce);
+
+            try {
+                await program.methods
+                    .createInvoice(testAgentId, staleNonce, paymentAmount, expiresAt, { fixed: {} }, null)
+                    .accounts({
+                        invoice: staleInvoice,
+                        nonceTracker,
+                        payer: payer.publicKey,
+                        agent: agentPda,
+                        recipient: recipient.publicKey,
+                        mint: usdcMint,
+                        acceptedMint,
+                        integrator: null,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+                assert.fail("Stale nonce should be rejected");
+            } catch (err: any) {
+                assert.include(err.toString(), "NonceAlreadyUsed");
+            }
+        });
+
+        it("❌ Should reject a recipient that does not own the agent", async () => {
+            const nonce = invoiceNonce.addn(100);
+            const [otherInvoice] = invoiceAddress(nonce);
+
+            try {
+                await program.methods
+                    .createInvoice(testAgentId, nonce, paymentAmount, expiresAt, { fixed: {} }, null)
+                    .accounts({
+                        invoice: otherInvoice,
+                        nonceTracker,
+                        payer: payer.publicKey,
+                        agent: agentPda,
+                        recipient: Keypair.generate().publicKey,
+                        mint: usdcMint,
+                        acceptedMint,
+                        integrator: null,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+                assert.fail("Recipient must be the agent owner");
+            } catch (err: any) {
+                assert.include(err.toString(), "RecipientMismatch");
+            }
+        });
     });
 
     describe("3. Settle Payment", () => {
         it("✅ Should settle payment with signature", async () => {
             console.log("\n📝 Test: Settle Payment");
 
-            const dummySignature = new Array(64).fill(0);
+            // Payer signs the canonical payment intent
+            intentIx = Ed25519Program.createInstructionWithPrivateKey({
+                privateKey: payer.payer.secretKey,
+                message: paymentIntentMessage(
+                    invoicePda,
+                    paymentAmount,
+                    recipient.publicKey,
+                    usdcMint,
+                    expiresAt
+                ),
+            });
+            // Signature follows the 16-byte header and 32-byte public key
+            const intentSignature = Array.from(intentIx.data.subarray(48, 112));
 
             [paymentPda] = PublicKey.findProgramAddressSync(
                 [Buffer.from("payment"), invoicePda.toBuffer()],
@@ -182,11 +692,14 @@ describe("SynapsePay Payments Tests", () => {
             );
 
             const tx = await program.methods
-                .settlePayment(dummySignature)
+                .settlePayment(intentSignature)
                 .accounts({
                     payer: payer.publicKey,
                     invoice: invoicePda,
                     payment: paymentPda,
+                    platformConfig,
+                    // No verified volume yet, so the base rate applies
+                    payerStats,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -215,27 +728,80 @@ describe("SynapsePay Payments Tests", () => {
                 program.programId
             );
 
-            // Create escrow token account
-            const escrowTokenAccount = await createAccount(
-                provider.connection,
-                payer.payer,
-                usdcMint,
-                escrowAuthority,
-                undefined,
-                undefined,
-                TOKEN_PROGRAM_ID
-            );
+            // The escrow token account is created by the program on first use
+
+            const verifyWith = (payerToken: PublicKey) =>
+                program.methods
+                    .verifyPayment()
+                    .accounts({
+                        payer: payer.publicKey,
+                        payment: paymentPda,
+                        invoice: invoicePda,
+                        mint: usdcMint,
+                        acceptedMint,
+                        platformConfig,
+                        referralAccrual: null,
+                        payerStats,
+                        payerTokenAccount: payerToken,
+                        escrowAccount: escrowPda,
+                        escrowAuthority,
+                        feeTreasury,
+                        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .preInstructions([intentIx])
+                    .rpc();
+
+            await expectError(verifyWith(payerOtherMintAccount), "InvalidMint");
+            await expectError(verifyWith(recipientTokenAccount), "InvalidTokenOwner");
+            // Without the Ed25519 intent instruction the program must reject
+            try {
+                await program.methods
+                    .verifyPayment()
+                    .accounts({
+                        payer: payer.publicKey,
+                        payment: paymentPda,
+                        invoice: invoicePda,
+                        mint: usdcMint,
+                        acceptedMint,
+                        platformConfig,
+                        referralAccrual: null,
+                        payerStats,
+                        payerTokenAccount,
+                        escrowAccount: escrowPda,
+                        escrowAuthority,
+                        feeTreasury,
+                        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+                assert.fail("verifyPayment should require a signed payment intent");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidSignature");
+            }
 
             const tx = await program.methods
                 .verifyPayment()
                 .accounts({
                     payer: payer.publicKey,
                     payment: paymentPda,
+                    invoice: invoicePda,
+                    mint: usdcMint,
+                    acceptedMint,
+                    platformConfig,
+                    referralAccrual: null,
+                    payerStats,
                     payerTokenAccount,
-                    escrowAccount: escrowTokenAccount,
+                    escrowAccount: escrowPda,
+                    escrowAuthority,
                     feeTreasury,
+                    instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
                     tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
                 })
+                .preInstructions([intentIx])
                 .rpc();
 
             console.log("✓ Transaction signature:", tx);
@@ -246,7 +812,7 @@ describe("SynapsePay Payments Tests", () => {
             // Verify escrow balance
             const escrowBalance = await getAccount(
                 provider.connection,
-                escrowTokenAccount
+                escrowPda
             );
             assert.equal(escrowBalance.amount.toString(), "950000");
 
@@ -264,6 +830,21 @@ describe("SynapsePay Payments Tests", () => {
     });
 
     describe("5. Complete Task", () => {
+        it("❌ Should reject a result CID longer than 64 bytes", async () => {
+            await expectError(
+                program.methods
+                    .completeTask("Q".repeat(65))
+                    .accounts({
+                        authority: payer.publicKey,
+                        platformConfig,
+                        payment: paymentPda,
+                        invoice: invoicePda,
+                    })
+                    .rpc(),
+                "ResultCidTooLong"
+            );
+        });
+
         it("✅ Should mark task as completed with result CID", async () => {
             console.log("\n📝 Test: Complete Task");
 
@@ -273,7 +854,9 @@ describe("SynapsePay Payments Tests", () => {
                 .completeTask(resultCid)
                 .accounts({
                     authority: payer.publicKey,
+                    platformConfig,
                     payment: paymentPda,
+                    invoice: invoicePda,
                 })
                 .rpc();
 
@@ -283,6 +866,13 @@ describe("SynapsePay Payments Tests", () => {
 
             assert.equal(Object.keys(paymentAccount.state)[0], "completed");
             assert.equal(paymentAccount.resultCid, resultCid);
+            assert.isTrue(paymentAccount.claimableAt.gt(paymentAccount.completedAt));
+
+            // The claim delay always covers the dispute window
+            const config = await program.account.platformConfig.fetch(platformConfig);
+            assert.isTrue(
+                paymentAccount.claimableAt.gte(paymentAccount.completedAt.add(config.disputeWindow))
+            );
 
             console.log("✓ Task completed successfully");
             console.log("  - Result CID:", paymentAccount.resultCid);
@@ -293,17 +883,43 @@ describe("SynapsePay Payments Tests", () => {
         it("✅ Should allow recipient to claim payment", async () => {
             console.log("\n📝 Test: Claim Payment");
 
-            const [escrowTokenAccount] = await provider.connection.getTokenAccountsByOwner(
-                escrowAuthority,
-                { mint: usdcMint }
-            );
+            const claimWith = (recipientToken: PublicKey) =>
+                program.methods
+                    .claimPayment()
+                    .accounts({
+                        recipient: recipient.publicKey,
+                        platformConfig,
+                        payment: paymentPda,
+                        invoice: invoicePda,
+                        mint: usdcMint,
+                        escrowAccount: escrowPda,
+                        recipientTokenAccount: recipientToken,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc();
+
+            await expectError(claimWith(recipientOtherMintAccount), "InvalidMint");
+            await expectError(claimWith(payerTokenAccount), "InvalidTokenOwner");
+
+            // The challenge window blocks the claim until the payer accepts the result
+            await expectError(claimWith(recipientTokenAccount), "ChallengePeriodActive");
+
+            await program.methods
+                .acceptResult()
+                .accounts({ payer: payer.publicKey, payment: paymentPda })
+                .rpc();
 
             const tx = await program.methods
                 .claimPayment()
                 .accounts({
                     recipient: recipient.publicKey,
+                    platformConfig,
                     payment: paymentPda,
-                    escrowAccount: escrowTokenAccount.pubkey,
+                    invoice: invoicePda,
+                    mint: usdcMint,
+                    escrowAccount: escrowPda,
                     recipientTokenAccount,
                     escrowAuthority,
                     tokenProgram: TOKEN_PROGRAM_ID,
@@ -328,6 +944,1899 @@ describe("SynapsePay Payments Tests", () => {
         });
     });
 
+    describe("6b. Close Payment", () => {
+        it("✅ Should close claimed payment, escrow and invoice to reclaim rent", async () => {
+            console.log("\n📝 Test: Close Payment and Invoice");
+
+            await program.methods
+                .closePayment()
+                .accounts({
+                    caller: payer.publicKey,
+                    payment: paymentPda,
+                    invoice: invoicePda,
+                    mint: usdcMint,
+                    escrowAccount: escrowPda,
+                    escrowAuthority,
+                    payer: payer.publicKey,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const invoiceAccount = await program.account.invoice.fetch(invoicePda);
+            assert.equal(Object.keys(invoiceAccount.state)[0], "claimed");
+
+            await program.methods
+                .closeInvoice()
+                .accounts({
+                    caller: payer.publicKey,
+                    invoice: invoicePda,
+                    payer: payer.publicKey,
+                })
+                .rpc();
+
+            assert.isNull(await provider.connection.getAccountInfo(paymentPda));
+            assert.isNull(await provider.connection.getAccountInfo(escrowPda));
+            assert.isNull(await provider.connection.getAccountInfo(invoicePda));
+
+            console.log("✓ Payment, escrow and invoice closed");
+        });
+
+        it("✅ Should settle, claim and close a payment in a Token-2022 transfer-fee mint", async () => {
+            console.log("\n📝 Test: Transfer-Fee Mint Lifecycle");
+
+            // 1% transfer fee, withheld on every destination account
+            const feeMintKeypair = Keypair.generate();
+            const feeMint = feeMintKeypair.publicKey;
+            const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+            await sendAndConfirmTransaction(
+                provider.connection,
+                new Transaction().add(
+                    SystemProgram.createAccount({
+                        fromPubkey: payer.publicKey,
+                        newAccountPubkey: feeMint,
+                        space: mintLen,
+                        lamports: await provider.connection.getMinimumBalanceForRentExemption(mintLen),
+                        programId: TOKEN_2022_PROGRAM_ID,
+                    }),
+                    createInitializeTransferFeeConfigInstruction(
+                        feeMint,
+                        payer.publicKey,
+                        payer.publicKey,
+                        100,
+                        BigInt(1_000_000),
+                        TOKEN_2022_PROGRAM_ID
+                    ),
+                    createInitializeMintInstruction(feeMint, 6, payer.publicKey, null, TOKEN_2022_PROGRAM_ID)
+                ),
+                [payer.payer, feeMintKeypair]
+            );
+
+            const payerFeeAccount = await createAccount(
+                provider.connection,
+                payer.payer,
+                feeMint,
+                payer.publicKey,
+                undefined,
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            const recipientFeeAccount = await createAccount(
+                provider.connection,
+                payer.payer,
+                feeMint,
+                recipient.publicKey,
+                undefined,
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            await mintTo(
+                provider.connection,
+                payer.payer,
+                feeMint,
+                payerFeeAccount,
+                payer.publicKey,
+                10_000_000,
+                [],
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            const [feeAcceptedMint] = PublicKey.findProgramAddressSync(
+                [Buffer.from("accepted_mint"), feeMint.toBuffer()],
+                program.programId
+            );
+            const [feeMintTreasury] = PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_treasury"), feeMint.toBuffer()],
+                program.programId
+            );
+            await program.methods
+                .addAcceptedMint()
+                .accounts({
+                    admin: payer.publicKey,
+                    platformConfig,
+                    platformAuthority,
+                    mint: feeMint,
+                    acceptedMint: feeAcceptedMint,
+                    feeTreasury: feeMintTreasury,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            await registry.methods
+                .setAcceptedMints([usdcMint, feeMint])
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+
+            const nonce = invoiceNonce.addn(10);
+            const [invoice] = invoiceAddress(nonce);
+            const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 300);
+            await program.methods
+                .createInvoice(testAgentId, nonce, paymentAmount, expiry, { fixed: {} }, null)
+                .accounts({
+                    invoice,
+                    nonceTracker,
+                    payer: payer.publicKey,
+                    agent: agentPda,
+                    recipient: recipient.publicKey,
+                    mint: feeMint,
+                    acceptedMint: feeAcceptedMint,
+                    integrator: null,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const ix = Ed25519Program.createInstructionWithPrivateKey({
+                privateKey: payer.payer.secretKey,
+                message: paymentIntentMessage(invoice, paymentAmount, recipient.publicKey, feeMint, expiry),
+            });
+            const [payment] = PublicKey.findProgramAddressSync(
+                [Buffer.from("payment"), invoice.toBuffer()],
+                program.programId
+            );
+            const escrow = escrowAddress(payment);
+            await program.methods
+                .settlePayment(Array.from(ix.data.subarray(48, 112)))
+                .accounts({
+                    payer: payer.publicKey,
+                    invoice,
+                    payment,
+                    platformConfig,
+                    payerStats,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            await program.methods
+                .verifyPayment()
+                .accounts({
+                    payer: payer.publicKey,
+                    payment,
+                    invoice,
+                    mint: feeMint,
+                    acceptedMint: feeAcceptedMint,
+                    platformConfig,
+                    referralAccrual: null,
+                    payerStats,
+                    payerTokenAccount: payerFeeAccount,
+                    escrowAccount: escrow,
+                    escrowAuthority,
+                    feeTreasury: feeMintTreasury,
+                    instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .preInstructions([ix])
+                .rpc();
+
+            // The 950000 sent to escrow arrives less the 1% transfer fee
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(paymentAccount.amount.toString(), "940500");
+
+            await program.methods
+                .completeTask("QmTransferFee")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+            await program.methods
+                .acceptResult()
+                .accounts({ payer: payer.publicKey, payment })
+                .rpc();
+            await program.methods
+                .claimPayment()
+                .accounts({
+                    recipient: recipient.publicKey,
+                    platformConfig,
+                    payment,
+                    invoice,
+                    mint: feeMint,
+                    escrowAccount: escrow,
+                    recipientTokenAccount: recipientFeeAccount,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .signers([recipient])
+                .rpc();
+
+            // The emptied escrow still holds the fee withheld on the way in
+            const escrowAccount = await getAccount(provider.connection, escrow, undefined, TOKEN_2022_PROGRAM_ID);
+            assert.equal(escrowAccount.amount.toString(), "0");
+            assert.equal(getTransferFeeAmount(escrowAccount)!.withheldAmount.toString(), "9500");
+
+            await program.methods
+                .closePayment()
+                .accounts({
+                    caller: payer.publicKey,
+                    payment,
+                    invoice,
+                    mint: feeMint,
+                    escrowAccount: escrow,
+                    escrowAuthority,
+                    payer: payer.publicKey,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                })
+                .rpc();
+
+            assert.isNull(await provider.connection.getAccountInfo(payment));
+            assert.isNull(await provider.connection.getAccountInfo(escrow));
+
+            await registry.methods
+                .setAcceptedMints([usdcMint])
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+
+            console.log("✓ Withheld fees harvested to the mint and escrow closed");
+        });
+
+        it("❌ Should not close a payment or invoice that is still executing", async () => {
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(15));
+
+            await expectError(
+                program.methods
+                    .closePayment()
+                    .accounts({
+                        caller: payer.publicKey,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        escrowAuthority,
+                        payer: payer.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc(),
+                "InvalidState"
+            );
+
+            await expectError(
+                program.methods
+                    .closeInvoice()
+                    .accounts({ caller: payer.publicKey, invoice, payer: payer.publicKey })
+                    .rpc(),
+                "InvalidState"
+            );
+        });
+    });
+
+    describe("6c. Refund Payment", () => {
+        it("✅ Should refund an executing payment only to the payer's account", async () => {
+            console.log("\n📝 Test: Refund Payment");
+
+            const { payment, escrow } = await openExecutingPayment(invoiceNonce.addn(20));
+
+            const refundWith = (payerToken: PublicKey, authorityPda = escrowAuthority) =>
+                program.methods
+                    .refundPayment()
+                    .accounts({
+                        authority: payer.publicKey,
+                        platformConfig,
+                        mint: usdcMint,
+                        payment,
+                        escrowAccount: escrow,
+                        payerTokenAccount: payerToken,
+                        escrowAuthority: authorityPda,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+
+            await expectError(refundWith(payerOtherMintAccount), "InvalidMint");
+            await expectError(refundWith(recipientTokenAccount), "InvalidTokenOwner");
+            await expectError(
+                refundWith(payerTokenAccount, payer.publicKey),
+                "InvalidEscrowAuthority"
+            );
+
+            // Only registered refund operators may refund; a revoked one is refused too
+            await program.methods
+                .grantRole({ refundOperator: {} }, recipient.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+            await program.methods
+                .revokeRole({ refundOperator: {} }, recipient.publicKey)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+            await expectError(
+                program.methods
+                    .refundPayment()
+                    .accounts({
+                        authority: recipient.publicKey,
+                        platformConfig,
+                        mint: usdcMint,
+                        payment,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc(),
+                "NotRefundOperator"
+            );
+
+            await refundWith(payerTokenAccount);
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "refunded");
+
+            console.log("✓ Payment refunded to payer");
+        });
+    });
+
+    describe("6d. Withdraw Fees", () => {
+        it("✅ Should withdraw fees only to the admin's account", async () => {
+            console.log("\n📝 Test: Withdraw Fees");
+
+            const withdrawWith = (adminToken: PublicKey) =>
+                program.methods
+                    .withdrawFees()
+                    .accounts({
+                        admin: payer.publicKey,
+                        platformConfig,
+                        mint: usdcMint,
+                        acceptedMint,
+                        feeTreasury,
+                        adminTokenAccount: adminToken,
+                        platformAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+
+            await expectError(withdrawWith(payerOtherMintAccount), "InvalidMint");
+            await expectError(withdrawWith(recipientTokenAccount), "InvalidTokenOwner");
+
+            await withdrawWith(payerTokenAccount);
+
+            const treasuryBalance = await getAccount(provider.connection, feeTreasury);
+            assert.equal(treasuryBalance.amount.toString(), "0");
+
+            console.log("✓ Fees withdrawn to admin");
+        });
+    });
+
+    describe("6e. Dispute", () => {
+        it("✅ Should block claims on a disputed payment and split it by arbitration", async () => {
+            console.log("\n📝 Test: Open and Resolve Dispute");
+
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(30));
+
+            await program.methods
+                .completeTask("QmBadResult")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+
+            // Only the payer may dispute
+            await expectError(
+                program.methods
+                    .openDispute()
+                    .accounts({ payer: recipient.publicKey, platformConfig, payment })
+                    .signers([recipient])
+                    .rpc(),
+                "Unauthorized"
+            );
+
+            await program.methods
+                .openDispute()
+                .accounts({ payer: payer.publicKey, platformConfig, payment })
+                .rpc();
+
+            let paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "disputed");
+
+            await expectError(
+                program.methods
+                    .claimPayment()
+                    .accounts({
+                        recipient: recipient.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc(),
+                "InvalidState"
+            );
+
+            const resolveWith = (bps: number) =>
+                program.methods
+                    .resolveDispute(bps)
+                    .accounts({
+                        arbiter: payer.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+
+            await expectError(resolveWith(10_001), "InvalidShareBps");
+
+            // Only a registered arbiter may resolve
+            await expectError(
+                program.methods
+                    .resolveDispute(5_000)
+                    .accounts({
+                        arbiter: recipient.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc(),
+                "NotArbiter"
+            );
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            const recipientBefore = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+
+            // 60% back to the payer, 40% to the recipient
+            await resolveWith(6_000);
+
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            const recipientAfter = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            assert.equal((payerAfter - payerBefore).toString(), "570000");
+            assert.equal((recipientAfter - recipientBefore).toString(), "380000");
+
+            paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "resolved");
+
+            console.log("✓ Dispute resolved 60/40");
+        });
+
+        it("❌ Should refuse disputes once the window has closed, releasing the claim", async () => {
+            console.log("\n📝 Test: Dispute Window Closed");
+
+            // Shrink both windows so they lapse within the test
+            const config = await program.account.platformConfig.fetch(platformConfig);
+            const setWindows = (dispute: anchor.BN, challenge: anchor.BN) =>
+                program.methods
+                    .updatePlatformConfig(null, null, null, dispute, challenge)
+                    .accounts({ admin: payer.publicKey, platformConfig })
+                    .rpc();
+            await setWindows(new anchor.BN(1), new anchor.BN(1));
+
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(35));
+            await program.methods
+                .completeTask("QmLateDispute")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+
+            await new Promise(resolve => setTimeout(resolve, 3000));
+
+            await expectError(
+                program.methods
+                    .openDispute()
+                    .accounts({ payer: payer.publicKey, platformConfig, payment })
+                    .rpc(),
+                "DisputeWindowClosed"
+            );
+
+            // No acceptance needed once the payer can no longer dispute
+            await program.methods
+                .claimPayment()
+                .accounts({
+                    recipient: recipient.publicKey,
+                    platformConfig,
+                    payment,
+                    invoice,
+                    mint: usdcMint,
+                    escrowAccount: escrow,
+                    recipientTokenAccount,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([recipient])
+                .rpc();
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "claimed");
+
+            await setWindows(config.disputeWindow, config.challengeWindow);
+
+            console.log("✓ Late dispute rejected and payment claimed");
+        });
+    });
+
+    describe("6f. SLA Deadline", () => {
+        it("✅ Should let anyone refund the payer once the agent misses its SLA", async () => {
+            console.log("\n📝 Test: Refund Overdue Payment");
+
+            // The agent owner declares a 1 second SLA
+            await registry.methods
+                .updateAgent(null, null, null, new anchor.BN(1))
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(40), 3);
+
+            // The SLA runs from funding rather than from the invoice expiry
+            const invoiceAccount = await program.account.invoice.fetch(invoice);
+            assert.equal(invoiceAccount.slaSeconds.toNumber(), 1);
+            assert.ok(invoiceAccount.executionDeadline.gte(invoiceAccount.createdAt.addn(1)));
+
+            const refundOverdue = () =>
+                program.methods
+                    .refundOverduePayment()
+                    .accounts({
+                        caller: recipient.publicKey,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc();
+
+            await expectError(refundOverdue(), "DeadlineNotReached");
+
+            await new Promise(resolve => setTimeout(resolve, 6000));
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await refundOverdue();
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            assert.equal((payerAfter - payerBefore).toString(), "950000");
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "refunded");
+
+            console.log("✓ Overdue payment refunded without a privileged signer");
+        });
+    });
+
+    describe("6g. Fail Task", () => {
+        it("✅ Should fail a task and refund escrow plus fee to the payer", async () => {
+            console.log("\n📝 Test: Fail Task");
+
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(50));
+
+            const failAs = (authority: Keypair, refundFee: boolean) =>
+                program.methods
+                    .failTask({ invalidInput: {} }, "QmErrorLog", refundFee)
+                    .accounts({
+                        authority: authority.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        acceptedMint,
+                        referralAccrual: null,
+                        escrowAccount: escrow,
+                        feeTreasury,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        platformAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([authority])
+                    .rpc();
+
+            // The agent may fail its own task but cannot hand back the platform fee
+            await expectError(failAs(recipient, true), "NotFacilitator");
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await failAs(payer.payer, true);
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            assert.equal((payerAfter - payerBefore).toString(), paymentAmount.toString());
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "failed");
+            assert.deepEqual(paymentAccount.failureReason, { invalidInput: {} });
+            assert.equal(paymentAccount.resultCid, "QmErrorLog");
+
+            console.log("✓ Task failed and payer fully refunded");
+        });
+
+        it("❌ Should not refund a fee the admin already withdrew", async () => {
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(55));
+
+            // Sweep the treasury, including this payment's fee
+            await program.methods
+                .withdrawFees()
+                .accounts({
+                    admin: payer.publicKey,
+                    platformConfig,
+                    mint: usdcMint,
+                    acceptedMint,
+                    feeTreasury,
+                    adminTokenAccount: payerTokenAccount,
+                    platformAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+
+            const failWith = (refundFee: boolean) =>
+                program.methods
+                    .failTask({ invalidInput: {} }, "QmErrorLog", refundFee)
+                    .accounts({
+                        authority: payer.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        acceptedMint,
+                        referralAccrual: null,
+                        escrowAccount: escrow,
+                        feeTreasury,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        platformAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+
+            await expectError(failWith(true), "InsufficientTreasury");
+
+            // The escrow itself can still be returned
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await failWith(false);
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            assert.equal((payerAfter - payerBefore).toString(), "950000");
+        });
+    });
+
+    describe("6h. Split Settlement", () => {
+        it("✅ Should split escrow between recipient and payer with both parties signing", async () => {
+            console.log("\n📝 Test: Settle Split");
+
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(60));
+            const stranger = Keypair.generate();
+
+            const splitWith = (coSigner: Keypair, recipientAmount: anchor.BN) =>
+                program.methods
+                    .settleSplit(recipientAmount)
+                    .accounts({
+                        authority: recipient.publicKey,
+                        coSigner: coSigner.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient, coSigner])
+                    .rpc();
+
+            await expectError(splitWith(stranger, new anchor.BN(700_000)), "SplitNotApproved");
+            await expectError(splitWith(payer.payer, new anchor.BN(950_001)), "InvalidSplitAmount");
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            const recipientBefore = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+
+            // 7 of 10 pages done: 70% of the escrow to the agent
+            await splitWith(payer.payer, new anchor.BN(665_000));
+
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            const recipientAfter = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            assert.equal((recipientAfter - recipientBefore).toString(), "665000");
+            assert.equal((payerAfter - payerBefore).toString(), "285000");
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "splitSettled");
+            assert.equal(paymentAccount.recipientShare.toString(), "665000");
+            assert.equal(paymentAccount.payerShare.toString(), "285000");
+
+            console.log("✓ Escrow split 70/30");
+        });
+    });
+
+    describe("6i. Authorize and Capture", () => {
+        it("✅ Should capture the actual cost of a hold and release the rest", async () => {
+            console.log("\n📝 Test: Capture Hold");
+
+            const { invoice, payment, escrow } = await openExecutingPayment(
+                invoiceNonce.addn(70),
+                300,
+                { hold: {} }
+            );
+
+            let paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(paymentAccount.authorizedAmount.toString(), paymentAmount.toString());
+            assert.equal(paymentAccount.platformFee.toString(), "0");
+
+            // Holds cannot be completed without a capture
+            await expectError(
+                program.methods
+                    .completeTask("QmUsageResult")
+                    .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                    .rpc(),
+                "CaptureRequired"
+            );
+
+            // Nor split, which would pay the recipient without a fee
+            await expectError(
+                program.methods
+                    .settleSplit(paymentAmount)
+                    .accounts({
+                        authority: recipient.publicKey,
+                        coSigner: payer.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        payerTokenAccount,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .signers([recipient])
+                    .rpc(),
+                "CaptureRequired"
+            );
+
+            const captureWith = (amount: anchor.BN) =>
+                program.methods
+                    .capturePayment("QmUsageResult", amount)
+                    .accounts({
+                        authority: payer.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        acceptedMint,
+                        referralAccrual: null,
+                        escrowAccount: escrow,
+                        feeTreasury,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+
+            await expectError(captureWith(paymentAmount.addn(1)), "CaptureExceedsHold");
+
+            // The agent has no usage pricing yet, so metered capture is unavailable
+            await expectError(
+                program.methods
+                    .captureUsage("QmUsageResult", new anchor.BN(1))
+                    .accounts({
+                        capture: {
+                            authority: payer.publicKey,
+                            platformConfig,
+                            payment,
+                            invoice,
+                            mint: usdcMint,
+                            acceptedMint,
+                            referralAccrual: null,
+                            escrowAccount: escrow,
+                            feeTreasury,
+                            payerTokenAccount,
+                            escrowAuthority,
+                            tokenProgram: TOKEN_PROGRAM_ID,
+                            systemProgram: SystemProgram.programId,
+                        },
+                        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    })
+                    .rpc(),
+                "MeteringNotEnabled"
+            );
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await captureWith(new anchor.BN(400_000));
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+
+            // 600000 released to the payer, 5% fee on the 400000 captured
+            assert.equal((payerAfter - payerBefore).toString(), "600000");
+
+            paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "completed");
+            assert.equal(paymentAccount.capturedAmount.toString(), "400000");
+            assert.equal(paymentAccount.platformFee.toString(), "20000");
+            assert.equal(paymentAccount.amount.toString(), "380000");
+
+            const escrowBalance = await getAccount(provider.connection, escrow);
+            assert.equal(escrowBalance.amount.toString(), "380000");
+
+            console.log("✓ Hold captured at 400000 of 1000000");
+        });
+    });
+
+    describe("6j. Metered Usage", () => {
+        it("✅ Should capture a hold from a usage report signed by the executor", async () => {
+            console.log("\n📝 Test: Capture Usage");
+
+            // The agent bills 2000 base units per 1k tokens, reported by its executor key
+            const executor = Keypair.generate();
+            await registry.methods
+                .setUsagePricing(executor.publicKey, { tokens1k: {} }, new anchor.BN(2_000))
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+
+            const { invoice, payment, escrow } = await openExecutingPayment(
+                invoiceNonce.addn(80),
+                300,
+                { hold: {} }
+            );
+
+            const resultCid = "QmMeteredResult";
+            const units = new anchor.BN(150);
+            const usageReport = Buffer.concat([
+                Buffer.from("synapsepay:usage-report:v1"),
+                payment.toBuffer(),
+                units.toArrayLike(Buffer, "le", 8),
+                Buffer.from(resultCid),
+            ]);
+
+            const captureSignedBy = (signer: Keypair) =>
+                program.methods
+                    .captureUsage(resultCid, units)
+                    .accounts({
+                        capture: {
+                            authority: payer.publicKey,
+                            platformConfig,
+                            payment,
+                            invoice,
+                            mint: usdcMint,
+                            acceptedMint,
+                            referralAccrual: null,
+                            escrowAccount: escrow,
+                            feeTreasury,
+                            payerTokenAccount,
+                            escrowAuthority,
+                            tokenProgram: TOKEN_PROGRAM_ID,
+                            systemProgram: SystemProgram.programId,
+                        },
+                        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                    })
+                    .preInstructions([
+                        Ed25519Program.createInstructionWithPrivateKey({
+                            privateKey: signer.secretKey,
+                            message: usageReport,
+                        }),
+                    ])
+                    .rpc();
+
+            await expectError(captureSignedBy(Keypair.generate()), "InvalidSignature");
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await captureSignedBy(executor);
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+
+            // 150 units x 2000 = 300000 captured, 700000 released back to the payer
+            assert.equal((payerAfter - payerBefore).toString(), "700000");
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(paymentAccount.capturedAmount.toString(), "300000");
+            assert.equal(paymentAccount.platformFee.toString(), "15000");
+            assert.equal(paymentAccount.amount.toString(), "285000");
+
+            console.log("✓ Metered charge captured from signed usage report");
+        });
+    });
+
+    describe("6k. Receipt NFT", () => {
+        let mintedReceipt: PublicKey;
+
+        it("✅ Should mint a soulbound Token-2022 receipt carrying the payment metadata", async () => {
+            console.log("\n📝 Test: Mint Receipt");
+
+            const { invoice, payment } = await openExecutingPayment(invoiceNonce.addn(90));
+
+            await program.methods
+                .completeTask("QmReceiptResult")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+
+            const [receipt] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt"), payment.toBuffer()],
+                program.programId
+            );
+            const [receiptMint] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt_mint"), payment.toBuffer()],
+                program.programId
+            );
+            const [receiptAuthority] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt_authority")],
+                program.programId
+            );
+            const payerReceiptAccount = getAssociatedTokenAddressSync(
+                receiptMint,
+                payer.publicKey,
+                false,
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            await program.methods
+                .mintReceipt()
+                .accounts({
+                    payer: payer.publicKey,
+                    payment,
+                    invoice,
+                    receipt,
+                    receiptMint,
+                    receiptAuthority,
+                    payerReceiptAccount,
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const receiptAccount = await program.account.receipt.fetch(receipt);
+            assert.ok(receiptAccount.mint.equals(receiptMint));
+            mintedReceipt = receipt;
+
+            const holding = await getAccount(
+                provider.connection,
+                payerReceiptAccount,
+                undefined,
+                TOKEN_2022_PROGRAM_ID
+            );
+            assert.equal(holding.amount.toString(), "1");
+
+            const metadata = await getTokenMetadata(provider.connection, receiptMint);
+            const fields = Object.fromEntries(metadata.additionalMetadata);
+            assert.equal(fields["agent_id"], testAgentId);
+            assert.equal(fields["result_cid"], "QmReceiptResult");
+            assert.equal(fields["amount"], receiptAccount.amount.toString());
+            assert.equal(fields["slot"], receiptAccount.slot.toString());
+
+            console.log("✓ Soulbound receipt minted to payer");
+        });
+
+        it("✅ Should mint a receipt whose mint address was pre-funded", async () => {
+            const { invoice, payment } = await openExecutingPayment(invoiceNonce.addn(95));
+
+            await program.methods
+                .completeTask("QmReceiptResult")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+
+            const [receipt] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt"), payment.toBuffer()],
+                program.programId
+            );
+            const [receiptMint] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt_mint"), payment.toBuffer()],
+                program.programId
+            );
+            const [receiptAuthority] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt_authority")],
+                program.programId
+            );
+
+            // Anyone can send lamports to the predictable mint address ahead of time
+            await sendAndConfirmTransaction(
+                provider.connection,
+                new Transaction().add(
+                    SystemProgram.transfer({
+                        fromPubkey: payer.publicKey,
+                        toPubkey: receiptMint,
+                        lamports: 1_000_000,
+                    })
+                ),
+                [payer.payer]
+            );
+
+            await program.methods
+                .mintReceipt()
+                .accounts({
+                    payer: payer.publicKey,
+                    payment,
+                    invoice,
+                    receipt,
+                    receiptMint,
+                    receiptAuthority,
+                    payerReceiptAccount: getAssociatedTokenAddressSync(
+                        receiptMint,
+                        payer.publicKey,
+                        false,
+                        TOKEN_2022_PROGRAM_ID
+                    ),
+                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const mintInfo = await provider.connection.getAccountInfo(receiptMint);
+            assert.ok(mintInfo.owner.equals(TOKEN_2022_PROGRAM_ID));
+        });
+
+        it("✅ Should close a receipt and return its rent to the payer", async () => {
+            const closeAs = (signer: Keypair) =>
+                program.methods
+                    .closeReceipt()
+                    .accounts({ payer: signer.publicKey, receipt: mintedReceipt })
+                    .signers([signer])
+                    .rpc();
+
+            await expectError(closeAs(recipient), "Unauthorized");
+
+            const rent = await provider.connection.getBalance(mintedReceipt);
+            const payerBefore = await provider.connection.getBalance(payer.publicKey);
+            const tx = await closeAs(payer.payer);
+
+            // The payer also paid this transaction's fee
+            const { meta } = await provider.connection.getTransaction(tx, {
+                commitment: "confirmed",
+                maxSupportedTransactionVersion: 0,
+            });
+            const payerAfter = await provider.connection.getBalance(payer.publicKey);
+            assert.equal(payerAfter - payerBefore + meta.fee, rent);
+            assert.isNull(await provider.connection.getAccountInfo(mintedReceipt));
+        });
+    });
+
+    describe("6l. Compressed Receipts", () => {
+        it("✅ Should append a receipt leaf and verify its Merkle proof", async () => {
+            console.log("\n📝 Test: Compressed Receipt");
+
+            const sha256 = (...parts: Buffer[]) =>
+                createHash("sha256").update(Buffer.concat(parts)).digest();
+            const u32 = (n: number) => new anchor.BN(n).toArrayLike(Buffer, "le", 4);
+
+            const [receiptTree] = PublicKey.findProgramAddressSync(
+                [Buffer.from("receipt_tree")],
+                program.programId
+            );
+            await program.methods
+                .initializeReceiptTree()
+                .accounts({ admin: payer.publicKey, platformConfig, receiptTree })
+                .rpc();
+
+            const { invoice, payment } = await openExecutingPayment(invoiceNonce.addn(100));
+            await program.methods
+                .completeTask("QmCompressedResult")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+
+            await program.methods
+                .mintCompressedReceipt()
+                .accounts({ payer: payer.publicKey, payment, invoice, receiptTree })
+                .rpc();
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "receiptMinted");
+
+            // The slot is not known up front; read it from the transaction logs
+            const tree = await program.account.receiptTree.fetch(receiptTree);
+            const leafIndex = tree.nextIndex.subn(1);
+            const signatures = await provider.connection.getSignaturesForAddress(receiptTree, { limit: 1 });
+            const leaf = {
+                payment,
+                payer: payer.publicKey,
+                agentId: testAgentId,
+                amount: paymentAccount.amount.add(paymentAccount.platformFee),
+                resultCid: "QmCompressedResult",
+                slot: new anchor.BN(signatures[0].slot),
+            };
+
+            // Proof for the rightmost leaf: left siblings are in filled_subtrees,
+            // right siblings are still empty subtrees
+            const proof: number[][] = [];
+            let zero = Buffer.alloc(32);
+            for (let level = 0; level < tree.filledSubtrees.length; level++) {
+                const isRight = leafIndex.shrn(level).andln(1) === 1;
+                proof.push(isRight ? tree.filledSubtrees[level] : Array.from(zero));
+                zero = sha256(zero, zero);
+            }
+
+            const leafHash = sha256(
+                Buffer.from("synapsepay:receipt-leaf:v1"),
+                leaf.payment.toBuffer(),
+                leaf.payer.toBuffer(),
+                u32(leaf.agentId.length),
+                Buffer.from(leaf.agentId),
+                leaf.amount.toArrayLike(Buffer, "le", 8),
+                u32(leaf.resultCid.length),
+                Buffer.from(leaf.resultCid),
+                leaf.slot.toArrayLike(Buffer, "le", 8)
+            );
+            let node = leafHash;
+            proof.forEach((sibling, level) => {
+                node = leafIndex.shrn(level).andln(1) === 1
+                    ? sha256(Buffer.from(sibling), node)
+                    : sha256(node, Buffer.from(sibling));
+            });
+            assert.deepEqual(Array.from(node), tree.root);
+
+            await program.methods
+                .verifyReceiptProof(leaf, leafIndex, proof)
+                .accounts({ receiptTree })
+                .rpc();
+
+            // Tampering with any receipt field breaks the proof
+            await expectError(
+                program.methods
+                    .verifyReceiptProof({ ...leaf, amount: leaf.amount.addn(1) }, leafIndex, proof)
+                    .accounts({ receiptTree })
+                    .rpc(),
+                "InvalidReceiptProof"
+            );
+
+            console.log("✓ Compressed receipt appended and proven");
+        });
+    });
+
+    describe("6m. Payment Channels", () => {
+        it("✅ Should redeem cumulative vouchers and refund the unspent deposit on close", async () => {
+            console.log("\n📝 Test: Payment Channel");
+
+            const nonce = invoiceNonce.addn(110);
+            const deposit = new anchor.BN(1_000_000);
+            const [channel] = PublicKey.findProgramAddressSync(
+                [
+                    Buffer.from("channel"),
+                    payer.publicKey.toBuffer(),
+                    agentPda.toBuffer(),
+                    nonce.toArrayLike(Buffer, "le", 8),
+                ],
+                program.programId
+            );
+            const [channelEscrow] = PublicKey.findProgramAddressSync(
+                [Buffer.from("channel_escrow"), channel.toBuffer()],
+                program.programId
+            );
+
+            await program.methods
+                .openChannel(testAgentId, nonce, deposit, new anchor.BN(600))
+                .accounts({
+                    payer: payer.publicKey,
+                    platformConfig,
+                    agent: agentPda,
+                    recipient: recipient.publicKey,
+                    mint: usdcMint,
+                    acceptedMint,
+                    channel,
+                    nonceTracker,
+                    payerTokenAccount,
+                    channelEscrow,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            // Voucher: prefix || channel || cumulative amount, signed by the payer
+            const voucher = (amount: anchor.BN, signer: Keypair = payer.payer) =>
+                Ed25519Program.createInstructionWithPrivateKey({
+                    privateKey: signer.secretKey,
+                    message: Buffer.concat([
+                        Buffer.from("synapsepay:channel-voucher:v1"),
+                        channel.toBuffer(),
+                        amount.toArrayLike(Buffer, "le", 8),
+                    ]),
+                });
+            // The channel keeps the fee rate in force when it was opened
+            const config = await program.account.platformConfig.fetch(platformConfig);
+            const channelAccount = await program.account.channel.fetch(channel);
+            assert.equal(channelAccount.feeBps, config.feeBps);
+            assert.equal(channelAccount.minFee.toString(), config.minFee.toString());
+
+            const checkpoint = {
+                recipient: recipient.publicKey,
+                channel,
+                mint: usdcMint,
+                acceptedMint,
+                channelEscrow,
+                recipientTokenAccount,
+                feeTreasury,
+                escrowAuthority,
+                instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            };
+
+            await expectError(
+                program.methods
+                    .checkpointChannel(new anchor.BN(200_000))
+                    .accounts(checkpoint)
+                    .preInstructions([voucher(new anchor.BN(200_000), Keypair.generate())])
+                    .signers([recipient])
+                    .rpc(),
+                "InvalidSignature"
+            );
+
+            await program.methods
+                .checkpointChannel(new anchor.BN(200_000))
+                .accounts(checkpoint)
+                .preInstructions([voucher(new anchor.BN(200_000))])
+                .signers([recipient])
+                .rpc();
+
+            // An older voucher cannot be replayed once a later one was redeemed
+            await expectError(
+                program.methods
+                    .checkpointChannel(new anchor.BN(100_000))
+                    .accounts(checkpoint)
+                    .preInstructions([voucher(new anchor.BN(100_000))])
+                    .signers([recipient])
+                    .rpc(),
+                "VoucherNotIncreasing"
+            );
+
+            // The payer must wait out the timeout before closing unilaterally
+            await program.methods
+                .requestChannelClose()
+                .accounts({ payer: payer.publicKey, channel })
+                .rpc();
+            await expectError(
+                program.methods
+                    .finalizeChannelClose()
+                    .accounts({
+                        payer: payer.publicKey,
+                        channel,
+                        mint: usdcMint,
+                        channelEscrow,
+                        payerTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc(),
+                "ChannelTimeoutNotElapsed"
+            );
+
+            // The agent answers with its latest voucher and closes cooperatively
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await program.methods
+                .closeChannel(new anchor.BN(350_000))
+                .accounts({
+                    checkpoint,
+                    payer: payer.publicKey,
+                    payerTokenAccount,
+                })
+                .preInstructions([voucher(new anchor.BN(350_000))])
+                .signers([recipient])
+                .rpc();
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+
+            assert.equal((payerAfter - payerBefore).toString(), "650000");
+            assert.isNull(await provider.connection.getAccountInfo(channel));
+            assert.isNull(await provider.connection.getAccountInfo(channelEscrow));
+
+            console.log("✓ Channel redeemed 350000 over two vouchers and refunded the rest");
+        });
+    });
+
+    describe("6n. Payment Streams", () => {
+        it("✅ Should stream per second and refund the unstreamed remainder on stop", async () => {
+            console.log("\n📝 Test: Payment Stream");
+
+            const nonce = invoiceNonce.addn(120);
+            const deposit = new anchor.BN(1_000_000);
+            const [stream] = PublicKey.findProgramAddressSync(
+                [
+                    Buffer.from("stream"),
+                    payer.publicKey.toBuffer(),
+                    agentPda.toBuffer(),
+                    nonce.toArrayLike(Buffer, "le", 8),
+                ],
+                program.programId
+            );
+            const [streamEscrow] = PublicKey.findProgramAddressSync(
+                [Buffer.from("stream_escrow"), stream.toBuffer()],
+                program.programId
+            );
+
+            // 10_000 per second drains the deposit in 100 seconds
+            await program.methods
+                .createStream(testAgentId, nonce, deposit, new anchor.BN(10_000))
+                .accounts({
+                    payer: payer.publicKey,
+                    platformConfig,
+                    agent: agentPda,
+                    recipient: recipient.publicKey,
+                    mint: usdcMint,
+                    acceptedMint,
+                    stream,
+                    nonceTracker,
+                    payerTokenAccount,
+                    streamEscrow,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const withdrawAs = (authority: Keypair) => ({
+                authority: authority.publicKey,
+                stream,
+                mint: usdcMint,
+                acceptedMint,
+                streamEscrow,
+                recipientTokenAccount,
+                feeTreasury,
+                escrowAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            });
+
+            await new Promise(resolve => setTimeout(resolve, 3000));
+
+            await expectError(
+                program.methods
+                    .withdrawStream()
+                    .accounts(withdrawAs(Keypair.generate()))
+                    .rpc(),
+                "Unauthorized"
+            );
+
+            // The payer may stop the stream but not trigger fee-bearing withdrawals
+            await expectError(
+                program.methods
+                    .withdrawStream()
+                    .accounts(withdrawAs(payer.payer))
+                    .rpc(),
+                "Unauthorized"
+            );
+
+            await program.methods
+                .withdrawStream()
+                .accounts(withdrawAs(recipient))
+                .signers([recipient])
+                .rpc();
+            const streamAccount = await program.account.stream.fetch(stream);
+            assert.isTrue(streamAccount.withdrawn.gtn(0));
+
+            // The payer stops the stream; accrued funds settle and the rest comes back
+            const recipientBefore = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            const treasuryBefore = (await getAccount(provider.connection, feeTreasury)).amount;
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await program.methods
+                .stopStream()
+                .accounts({
+                    withdraw: withdrawAs(payer.payer),
+                    payer: payer.publicKey,
+                    payerTokenAccount,
+                })
+                .rpc();
+            const recipientAfter = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            const treasuryAfter = (await getAccount(provider.connection, feeTreasury)).amount;
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+
+            const settled = (recipientAfter - recipientBefore) + (treasuryAfter - treasuryBefore);
+            const refunded = payerAfter - payerBefore;
+            assert.equal(
+                (BigInt(streamAccount.withdrawn.toString()) + settled + refunded).toString(),
+                deposit.toString()
+            );
+            assert.isTrue(refunded > BigInt(0));
+            assert.isNull(await provider.connection.getAccountInfo(stream));
+            assert.isNull(await provider.connection.getAccountInfo(streamEscrow));
+
+            console.log("✓ Stream stopped with", refunded.toString(), "refunded to payer");
+        });
+    });
+
+    describe("6o. Revenue Splits", () => {
+        it("✅ Should split a claim across the payees snapshotted on the invoice", async () => {
+            console.log("\n📝 Test: Revenue Split");
+
+            const dataProvider = Keypair.generate();
+            const dataProviderTokenAccount = await createAccount(
+                provider.connection,
+                payer.payer,
+                usdcMint,
+                dataProvider.publicKey
+            );
+
+            const setPayees = (payees: { wallet: PublicKey; shareBps: number }[]) =>
+                registry.methods
+                    .setPayees(payees)
+                    .accounts({ owner: recipient.publicKey, agent: agentPda })
+                    .signers([recipient])
+                    .rpc();
+
+            await setPayees([{ wallet: dataProvider.publicKey, shareBps: 2_500 }]);
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(130));
+
+            // Changing payees later cannot reroute funds already in escrow
+            await setPayees([]);
+
+            await program.methods
+                .completeTask("QmSplitResult")
+                .accounts({ authority: payer.publicKey, platformConfig, payment, invoice })
+                .rpc();
+            await program.methods
+                .acceptResult()
+                .accounts({ payer: payer.publicKey, payment })
+                .rpc();
+
+            const claim = (payeeAccounts: PublicKey[]) =>
+                program.methods
+                    .claimPayment()
+                    .accounts({
+                        recipient: recipient.publicKey,
+                        platformConfig,
+                        payment,
+                        invoice,
+                        mint: usdcMint,
+                        escrowAccount: escrow,
+                        recipientTokenAccount,
+                        escrowAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .remainingAccounts(
+                        payeeAccounts.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }))
+                    )
+                    .signers([recipient])
+                    .rpc();
+
+            await expectError(claim([]), "PayeeAccountsMismatch");
+            await expectError(claim([recipientTokenAccount]), "InvalidTokenOwner");
+
+            const recipientBefore = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            await claim([dataProviderTokenAccount]);
+            const recipientAfter = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            const providerBalance = (await getAccount(provider.connection, dataProviderTokenAccount)).amount;
+
+            // 25% of the 950000 escrow to the data provider, the rest to the owner
+            assert.equal(providerBalance.toString(), "237500");
+            assert.equal((recipientAfter - recipientBefore).toString(), "712500");
+
+            console.log("✓ Claim split between owner and data provider");
+        });
+
+        it("✅ Should pay payees their share of a split settlement", async () => {
+            const dataProvider = Keypair.generate();
+            const dataProviderTokenAccount = await createAccount(
+                provider.connection,
+                payer.payer,
+                usdcMint,
+                dataProvider.publicKey
+            );
+
+            await registry.methods
+                .setPayees([{ wallet: dataProvider.publicKey, shareBps: 2_500 }])
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+            const { invoice, payment, escrow } = await openExecutingPayment(invoiceNonce.addn(131));
+            await registry.methods
+                .setPayees([])
+                .accounts({ owner: recipient.publicKey, agent: agentPda })
+                .signers([recipient])
+                .rpc();
+
+            const recipientBefore = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await program.methods
+                .settleSplit(new anchor.BN(665_000))
+                .accounts({
+                    authority: recipient.publicKey,
+                    coSigner: payer.publicKey,
+                    platformConfig,
+                    payment,
+                    invoice,
+                    mint: usdcMint,
+                    escrowAccount: escrow,
+                    payerTokenAccount,
+                    recipientTokenAccount,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .remainingAccounts([{ pubkey: dataProviderTokenAccount, isWritable: true, isSigner: false }])
+                .signers([recipient])
+                .rpc();
+            const recipientAfter = (await getAccount(provider.connection, recipientTokenAccount)).amount;
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            const providerBalance = (await getAccount(provider.connection, dataProviderTokenAccount)).amount;
+
+            // 25% of the recipient's 665000 to the data provider; the payer's part is untouched
+            assert.equal(providerBalance.toString(), "166250");
+            assert.equal((recipientAfter - recipientBefore).toString(), "498750");
+            assert.equal((payerAfter - payerBefore).toString(), "285000");
+
+            console.log("✓ Split settlement shared with the data provider");
+        });
+    });
+
+    describe("6p. Referrals", () => {
+        it("✅ Should credit the referrer a share of the fee and let them withdraw it", async () => {
+            console.log("\n📝 Test: Referral Fees");
+
+            const referrer = Keypair.generate();
+            const referrerTokenAccount = await createAccount(
+                provider.connection,
+                payer.payer,
+                usdcMint,
+                referrer.publicKey
+            );
+
+            // 20% of the platform fee goes to referrers
+            await program.methods
+                .setReferralShare(2_000)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            // Only wallets the admin registered may be named as referrers
+            const addIntegrator = (wallet: PublicKey) =>
+                program.methods
+                    .addIntegrator(wallet)
+                    .accounts({
+                        admin: payer.publicKey,
+                        platformConfig,
+                        integrator: integratorAddress(wallet),
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+            const setIntegratorActive = (wallet: PublicKey, isActive: boolean) =>
+                program.methods
+                    .updateIntegrator(isActive)
+                    .accounts({ admin: payer.publicKey, platformConfig, integrator: integratorAddress(wallet) })
+                    .rpc();
+            await addIntegrator(referrer.publicKey);
+            await addIntegrator(payer.publicKey);
+
+            await setIntegratorActive(referrer.publicKey, false);
+            await expectError(
+                openExecutingPayment(invoiceNonce.addn(140), 300, { fixed: {} }, referrer.publicKey),
+                "ReferrerNotRegistered"
+            );
+            await setIntegratorActive(referrer.publicKey, true);
+
+            // Even a registered payer cannot refer itself into a rebate
+            await expectError(
+                openExecutingPayment(invoiceNonce.addn(140), 300, { fixed: {} }, payer.publicKey),
+                "InvalidReferrer"
+            );
+
+            // A referred payment cannot skip the referrer's accrual
+            await expectError(
+                openExecutingPayment(invoiceNonce.addn(140), 300, { fixed: {} }, referrer.publicKey, false),
+                "ReferralAccountMissing"
+            );
+
+            const { payment } = await openExecutingPayment(
+                invoiceNonce.addn(141),
+                300,
+                { fixed: {} },
+                referrer.publicKey
+            );
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(paymentAccount.referralFee.toString(), "10000");
+
+            const referralAccrual = referralAddress(referrer.publicKey);
+            const accrual = await program.account.referralAccrual.fetch(referralAccrual);
+            assert.equal(accrual.accrued.toString(), "10000");
+
+            await program.methods
+                .withdrawReferralFees()
+                .accounts({
+                    referrer: referrer.publicKey,
+                    referralAccrual,
+                    mint: usdcMint,
+                    acceptedMint,
+                    feeTreasury,
+                    referrerTokenAccount,
+                    platformAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([referrer])
+                .rpc();
+
+            const balance = (await getAccount(provider.connection, referrerTokenAccount)).amount;
+            assert.equal(balance.toString(), "10000");
+            const acceptedMintAccount = await program.account.acceptedMint.fetch(acceptedMint);
+            assert.equal(acceptedMintAccount.referralLiability.toString(), "0");
+
+            // Refunding a referred payment's fee takes the referrer's credit back
+            const refunded = await openExecutingPayment(
+                invoiceNonce.addn(142),
+                300,
+                { fixed: {} },
+                referrer.publicKey
+            );
+            const accruedBefore = (await program.account.referralAccrual.fetch(referralAccrual)).accrued;
+            assert.equal(accruedBefore.toString(), "10000");
+
+            const payerBefore = (await getAccount(provider.connection, payerTokenAccount)).amount;
+            await program.methods
+                .failTask({ invalidInput: {} }, null, true)
+                .accounts({
+                    authority: payer.publicKey,
+                    platformConfig,
+                    payment: refunded.payment,
+                    invoice: refunded.invoice,
+                    mint: usdcMint,
+                    acceptedMint,
+                    referralAccrual,
+                    escrowAccount: refunded.escrow,
+                    feeTreasury,
+                    payerTokenAccount,
+                    escrowAuthority,
+                    platformAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .rpc();
+            const payerAfter = (await getAccount(provider.connection, payerTokenAccount)).amount;
+
+            // Escrow plus the full fee, referral share included
+            assert.equal((payerAfter - payerBefore).toString(), paymentAmount.toString());
+            const reversed = await program.account.referralAccrual.fetch(referralAccrual);
+            assert.equal(reversed.accrued.toString(), "0");
+            const mintAfterRefund = await program.account.acceptedMint.fetch(acceptedMint);
+            assert.equal(mintAfterRefund.referralLiability.toString(), "0");
+
+            // A referred hold credits the referrer when its fee is captured
+            const held = await openExecutingPayment(
+                invoiceNonce.addn(143),
+                300,
+                { hold: {} },
+                referrer.publicKey
+            );
+            await program.methods
+                .capturePayment("QmReferredHold", new anchor.BN(400_000))
+                .accounts({
+                    authority: payer.publicKey,
+                    platformConfig,
+                    payment: held.payment,
+                    invoice: held.invoice,
+                    mint: usdcMint,
+                    acceptedMint,
+                    referralAccrual,
+                    escrowAccount: held.escrow,
+                    feeTreasury,
+                    payerTokenAccount,
+                    escrowAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            // 20% of the 20000 fee on the 400000 captured
+            const captured = await program.account.payment.fetch(held.payment);
+            assert.equal(captured.referralFee.toString(), "4000");
+            const credited = await program.account.referralAccrual.fetch(referralAccrual);
+            assert.equal(credited.accrued.toString(), "4000");
+
+            console.log("✓ Referrer credited and paid 10000");
+        });
+    });
+
+    describe("6q. Volume Fee Tiers", () => {
+        it("✅ Should settle at the fee tier matching the payer's rolling volume", async () => {
+            console.log("\n📝 Test: Fee Tiers");
+
+            const setTiers = (tiers: { minVolume: anchor.BN; feeBps: number }[]) =>
+                program.methods
+                    .setFeeTiers(tiers)
+                    .accounts({ admin: payer.publicKey, platformConfig })
+                    .rpc();
+
+            await expectError(
+                setTiers([
+                    { minVolume: new anchor.BN(5_000_000), feeBps: 300 },
+                    { minVolume: new anchor.BN(1_000_000), feeBps: 200 },
+                ]),
+                "InvalidFeeTiers"
+            );
+
+            // Tiers only ever discount the 5% base rate
+            await expectError(
+                setTiers([{ minVolume: new anchor.BN(1_000_000), feeBps: 600 }]),
+                "InvalidFeeTiers"
+            );
+
+            // Verification counts the escrowed amount and fee toward the payer's volume
+            const volumeBefore = (await program.account.payerStats.fetch(payerStats)).lifetimeVolume;
+            await openExecutingPayment(invoiceNonce.addn(150));
+            const stats = await program.account.payerStats.fetch(payerStats);
+            assert.equal(
+                stats.lifetimeVolume.sub(volumeBefore).toString(),
+                paymentAmount.toString()
+            );
+
+            // Payments verified so far total well over 3 USDC
+            assert.isTrue(stats.lifetimeVolume.gte(new anchor.BN(3_000_000)));
+
+            await setTiers([
+                { minVolume: new anchor.BN(1_000_000), feeBps: 300 },
+                { minVolume: new anchor.BN(3_000_000), feeBps: 200 },
+            ]);
+
+            const { payment } = await openExecutingPayment(invoiceNonce.addn(151));
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(paymentAccount.feeBps, 200);
+            assert.equal(paymentAccount.platformFee.toString(), "20000");
+
+            // A base rate lowered below the tiers still applies to high-volume payers
+            await program.methods
+                .updatePlatformConfig(100, null, null, null, null)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+            const discounted = await openExecutingPayment(invoiceNonce.addn(152));
+            const discountedAccount = await program.account.payment.fetch(discounted.payment);
+            assert.equal(discountedAccount.feeBps, 100);
+            assert.equal(discountedAccount.platformFee.toString(), "10000");
+
+            await program.methods
+                .updatePlatformConfig(500, null, null, null, null)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+            await setTiers([]);
+
+            console.log("✓ Payment settled at the 2% volume tier");
+        });
+    });
+
+    describe("6r. Expire Payment", () => {
+        const nonce = invoiceNonce.addn(160);
+        const [invoice] = invoiceAddress(nonce);
+        const [payment] = PublicKey.findProgramAddressSync(
+            [Buffer.from("payment"), invoice.toBuffer()],
+            program.programId
+        );
+
+        const expire = () =>
+            program.methods
+                .expirePayment()
+                .accounts({
+                    caller: payer.publicKey,
+                    platformConfig,
+                    payment,
+                    invoice,
+                    payer: payer.publicKey,
+                })
+                .rpc();
+
+        it("❌ Should not expire a pending payment before it times out", async () => {
+            console.log("\n📝 Test: Expire Payment");
+
+            // Settled but never funded
+            const expiry = new anchor.BN(Math.floor(Date.now() / 1000) + 300);
+            await program.methods
+                .createInvoice(testAgentId, nonce, paymentAmount, expiry, { fixed: {} }, null)
+                .accounts({
+                    invoice,
+                    nonceTracker,
+                    payer: payer.publicKey,
+                    agent: agentPda,
+                    recipient: recipient.publicKey,
+                    mint: usdcMint,
+                    acceptedMint,
+                    integrator: null,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const ix = Ed25519Program.createInstructionWithPrivateKey({
+                privateKey: payer.payer.secretKey,
+                message: paymentIntentMessage(invoice, paymentAmount, recipient.publicKey, usdcMint, expiry),
+            });
+            await program.methods
+                .settlePayment(Array.from(ix.data.subarray(48, 112)))
+                .accounts({
+                    payer: payer.publicKey,
+                    invoice,
+                    payment,
+                    platformConfig,
+                    payerStats,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            await expectError(expire(), "PaymentNotTimedOut");
+
+            const paymentAccount = await program.account.payment.fetch(payment);
+            assert.equal(Object.keys(paymentAccount.state)[0], "pending");
+        });
+
+        it("✅ Should expire a timed-out payment and refund its rent to the payer", async () => {
+            await program.methods
+                .updatePlatformConfig(null, null, new anchor.BN(1), null, null)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+            await new Promise(resolve => setTimeout(resolve, 2000));
+
+            const rent =
+                (await provider.connection.getBalance(payment)) +
+                (await provider.connection.getBalance(invoice));
+            const payerBefore = await provider.connection.getBalance(payer.publicKey);
+
+            const tx = await expire();
+
+            // The payer also paid this transaction's fee
+            const { meta } = await provider.connection.getTransaction(tx, {
+                commitment: "confirmed",
+                maxSupportedTransactionVersion: 0,
+            });
+            const payerAfter = await provider.connection.getBalance(payer.publicKey);
+            assert.equal(payerAfter - payerBefore + meta.fee, rent);
+
+            // Expired payments and their invoices are closed
+            assert.isNull(await provider.connection.getAccountInfo(payment));
+            assert.isNull(await provider.connection.getAccountInfo(invoice));
+
+            await program.methods
+                .updatePlatformConfig(null, null, new anchor.BN(3600), null, null)
+                .accounts({ admin: payer.publicKey, platformConfig })
+                .rpc();
+
+            console.log("✓ Timed-out payment expired and rent refunded");
+        });
+    });
+
+    describe("7. Expire Invoice", () => {
+        it("✅ Should let anyone expire a stale invoice and refund rent to payer", async () => {
+            console.log("\n📝 Test: Expire Invoice");
+
+            // Above every nonce used earlier, which the payer's tracker has already passed
+            const nonce = invoiceNonce.addn(1_000);
+            const [staleInvoice] = invoiceAddress(nonce);
+            const shortExpiry = new anchor.BN(Math.floor(Date.now() / 1000) + 2);
+
+            await program.methods
+                .createInvoice(testAgentId, nonce, paymentAmount, shortExpiry, { fixed: {} }, null)
+                .accounts({
+                    invoice: staleInvoice,
+                    nonceTracker,
+                    payer: payer.publicKey,
+                    agent: agentPda,
+                    recipient: recipient.publicKey,
+                    mint: usdcMint,
+                    acceptedMint,
+                    integrator: null,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            await new Promise(resolve => setTimeout(resolve, 4000));
+
+            // Called by the recipient to show expiry is permissionless
+            const tx = await program.methods
+                .expireInvoice()
+                .accounts({
+                    caller: recipient.publicKey,
+                    invoice: staleInvoice,
+                    payer: payer.publicKey,
+                })
+                .signers([recipient])
+                .rpc();
+
+            console.log("✓ Transaction signature:", tx);
+
+            const closed = await provider.connection.getAccountInfo(staleInvoice);
+            assert.isNull(closed);
+
+            console.log("✓ Stale invoice expired and closed");
+        });
+    });
+
     after(() => {
         console.log("\n✅ All Payments tests completed!");
     });
@@ -105,7 +105,7 @@ describe("SynapsePay Registry Tests", () => {
             const newPrice = new anchor.BN(2_000_000); // 2 USDC
 
             const tx = await program.methods
-                .updateAgent(newMetadataCid, newPrice)
+                .updateAgent(newMetadataCid, newPrice, new anchor.BN(7_200), new anchor.BN(600))
                 .accounts({
                     agent: agentPda,
                     owner: owner.publicKey,
@@ -119,6 +119,8 @@ describe("SynapsePay Registry Tests", () => {
 
             assert.equal(agentAccount.metadataCid, newMetadataCid);
             assert.equal(agentAccount.price.toString(), newPrice.toString());
+            assert.equal(agentAccount.challengeWindow.toString(), "7200");
+            assert.equal(agentAccount.slaSeconds.toString(), "600");
 
             console.log("✓ Agent updated successfully");
             console.log("  - New Metadata CID:", agentAccount.metadataCid);
@@ -132,7 +134,7 @@ describe("SynapsePay Registry Tests", () => {
 
             try {
                 await program.methods
-                    .updateAgent("QmHacker", new anchor.BN(1))
+                    .updateAgent("QmHacker", new anchor.BN(1), null, null)
                     .accounts({
                         agent: agentPda,
                         owner: unauthorizedUser.publicKey,
@@ -214,6 +216,28 @@ describe("SynapsePay Registry Tests", () => {
         });
     });
 
+    describe("6. Migrate Agent", () => {
+        it("❌ Should refuse to migrate an agent that already has the current layout", async () => {
+            console.log("\n📝 Test: Migrate Agent");
+
+            try {
+                await program.methods
+                    .migrateAgent()
+                    .accounts({
+                        payer: owner.publicKey,
+                        agent: agentPda,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+                assert.fail("Migrating a current agent should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "AgentAlreadyMigrated");
+            }
+
+            console.log("✓ Current agent left untouched");
+        });
+    });
+
     after(() => {
         console.log("\n✅ All Registry tests completed!");
     });
@@ -10,6 +10,8 @@ import {
 } from "@solana/spl-token";
 import { assert } from "chai";
 import { SynapsepayScheduler } from "../target/types/synapsepay_scheduler";
+import { SynapsepayPayments } from "../target/types/synapsepay_payments";
+import { SynapsepayRegistry } from "../target/types/synapsepay_registry";
 
 describe("SynapsePay Scheduler Tests", () => {
     const provider = anchor.AnchorProvider.env();
@@ -17,28 +19,96 @@ describe("SynapsePay Scheduler Tests", () => {
 
     const program = anchor.workspace.SynapsepayScheduler as Program<SynapsepayScheduler>;
 
+    const paymentsProgram = anchor.workspace.SynapsepayPayments as Program<SynapsepayPayments>;
+    const paymentsProgramId = paymentsProgram.programId;
+
+    const registry = anchor.workspace.SynapsepayRegistry as Program<SynapsepayRegistry>;
+
     const owner = provider.wallet;
 
     let usdcMint: PublicKey;
+    let otherMint: PublicKey;
+    let ownerOtherMintAccount: PublicKey;
+    let platformConfig: PublicKey;
+    let acceptedMint: PublicKey;
     let ownerTokenAccount: PublicKey;
     let vaultAuthority: PublicKey;
     let subscriptionPda: PublicKey;
     let subscriptionVault: PublicKey;
+    let agentPda: PublicKey;
 
     const testAgentId = "daily-report-agent";
 
     before(async () => {
         console.log("🔧 Setting up Scheduler test environment...");
 
-        // Create USDC mint
-        usdcMint = await createMint(
+        // Create a USDC mock mint and have the payments platform accept it
+        usdcMint = await createMint(provider.connection, owner.payer, owner.publicKey, null, 6);
+        console.log("✓ USDC Mint:", usdcMint.toBase58());
+
+        [platformConfig] = PublicKey.findProgramAddressSync(
+            [Buffer.from("platform_config")],
+            paymentsProgramId
+        );
+        const [platformAuthority] = PublicKey.findProgramAddressSync(
+            [Buffer.from("platform_authority")],
+            paymentsProgramId
+        );
+        const [paymentsEscrowAuthority] = PublicKey.findProgramAddressSync(
+            [Buffer.from("escrow_authority")],
+            paymentsProgramId
+        );
+        [acceptedMint] = PublicKey.findProgramAddressSync(
+            [Buffer.from("accepted_mint"), usdcMint.toBuffer()],
+            paymentsProgramId
+        );
+        const [feeTreasury] = PublicKey.findProgramAddressSync(
+            [Buffer.from("fee_treasury"), usdcMint.toBuffer()],
+            paymentsProgramId
+        );
+
+        // The payments suite may already have initialized the platform
+        const existingConfig = await provider.connection.getAccountInfo(platformConfig);
+        if (existingConfig === null) {
+            await paymentsProgram.methods
+                .initializePlatform(500, new anchor.BN(0)) // 5%, no minimum
+                .accounts({
+                    admin: owner.publicKey,
+                    platformAuthority,
+                    escrowAuthority: paymentsEscrowAuthority,
+                    usdcMint,
+                    acceptedMint,
+                    feeTreasury,
+                    platformConfig,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            console.log("✓ Payments platform initialized");
+        } else {
+            await paymentsProgram.methods
+                .addAcceptedMint()
+                .accounts({
+                    admin: owner.publicKey,
+                    platformConfig,
+                    platformAuthority,
+                    mint: usdcMint,
+                    acceptedMint,
+                    feeTreasury,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            console.log("✓ USDC Mint accepted by the payments platform");
+        }
+
+        otherMint = await createMint(provider.connection, owner.payer, owner.publicKey, null, 6);
+        ownerOtherMintAccount = await createAccount(
             provider.connection,
             owner.payer,
-            owner.publicKey,
-            null,
-            6
+            otherMint,
+            owner.publicKey
         );
-        console.log("✓ USDC Mint created:", usdcMint.toBase58());
 
         // Create owner token account
         ownerTokenAccount = await createAccount(
@@ -59,6 +129,21 @@ describe("SynapsePay Scheduler Tests", () => {
         );
         console.log("✓ Minted 100 USDC to owner");
 
+        // Register the subscribed agent at 1 USDC per run
+        [agentPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("agent"), Buffer.from(testAgentId)],
+            registry.programId
+        );
+        await registry.methods
+            .registerAgent(testAgentId, "QmDailyReport", new anchor.BN(1_000_000), { automation: {} })
+            .accounts({
+                agent: agentPda,
+                owner: owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+        console.log("✓ Agent registered:", agentPda.toBase58());
+
         // Derive PDAs
         [vaultAuthority] = PublicKey.findProgramAddressSync(
             [Buffer.from("subscription_vault_authority")],
@@ -139,27 +224,41 @@ describe("SynapsePay Scheduler Tests", () => {
                 program.programId
             );
 
-            // Create subscription vault token account
-            const vaultTokenAccount = await createAccount(
-                provider.connection,
-                owner.payer,
-                usdcMint,
-                vaultAuthority,
-                undefined,
-                undefined,
-                TOKEN_PROGRAM_ID
-            );
-
             const fundAmount = new anchor.BN(50_000_000); // 50 USDC
 
+            // Funding from a token account of another mint must be rejected
+            try {
+                await program.methods
+                    .fundSubscription(fundAmount)
+                    .accounts({
+                        owner: owner.publicKey,
+                        subscription: subscriptionPda,
+                        mint: usdcMint,
+                        acceptedMint,
+                        ownerTokenAccount: ownerOtherMintAccount,
+                        subscriptionVault,
+                        vaultAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .rpc();
+                assert.fail("Funding with another mint should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidMint");
+            }
+
             const tx = await program.methods
                 .fundSubscription(fundAmount)
                 .accounts({
                     owner: owner.publicKey,
                     subscription: subscriptionPda,
+                    mint: usdcMint,
+                    acceptedMint,
                     ownerTokenAccount,
-                    subscriptionVault: vaultTokenAccount,
+                    subscriptionVault,
+                    vaultAuthority,
                     tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
                 })
                 .rpc();
 
@@ -171,7 +270,7 @@ describe("SynapsePay Scheduler Tests", () => {
             // Verify vault balance
             const vaultBalance = await getAccount(
                 provider.connection,
-                vaultTokenAccount
+                subscriptionVault
             );
             assert.equal(vaultBalance.amount.toString(), fundAmount.toString());
 
@@ -258,45 +357,65 @@ describe("SynapsePay Scheduler Tests", () => {
             );
             await new Promise(resolve => setTimeout(resolve, 1000));
 
-            // Get vault token account
-            const [vaultTokenAccounts] = await provider.connection.getTokenAccountsByOwner(
-                vaultAuthority,
-                { mint: usdcMint }
-            );
-
             // Create payment escrow
+            const [paymentsEscrowAuthority] = PublicKey.findProgramAddressSync(
+                [Buffer.from("escrow_authority")],
+                paymentsProgramId
+            );
             const paymentEscrow = await createAccount(
                 provider.connection,
                 owner.payer,
                 usdcMint,
-                owner.publicKey
+                paymentsEscrowAuthority,
+                Keypair.generate()
             );
 
-            // Create fee treasury
-            const feeTreasury = await createAccount(
-                provider.connection,
-                owner.payer,
-                usdcMint,
-                owner.publicKey
+            // Fee treasury lives in the payments program
+            const [feeTreasury] = PublicKey.findProgramAddressSync(
+                [Buffer.from("fee_treasury"), usdcMint.toBuffer()],
+                paymentsProgramId
             );
 
-            // Note: This will fail if not time yet, but demonstrates the flow
-            try {
-                const tx = await program.methods
+            const triggerWith = (escrow: PublicKey, treasury: PublicKey) =>
+                program.methods
                     .triggerScheduledTask()
                     .accounts({
                         keeper: keeper.publicKey,
                         subscription: subscriptionPda,
-                        agent: owner.publicKey, // Placeholder
-                        subscriptionVault: vaultTokenAccounts.pubkey,
-                        paymentEscrow,
-                        feeTreasury,
+                        agent: agentPda,
+                        subscriptionVault,
+                        paymentEscrow: escrow,
+                        escrowAuthority: paymentsEscrowAuthority,
+                        platformConfig,
+                        mint: usdcMint,
+                        acceptedMint,
+                        feeTreasury: treasury,
                         vaultAuthority,
                         tokenProgram: TOKEN_PROGRAM_ID,
                     })
                     .signers([keeper])
                     .rpc();
 
+            // Payments must land in an escrow held by the payments program
+            try {
+                await triggerWith(ownerTokenAccount, feeTreasury);
+                assert.fail("Trigger into a foreign escrow should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidPaymentEscrow");
+            }
+
+            // Fees must go to the mint's registered treasury
+            try {
+                await triggerWith(paymentEscrow, ownerTokenAccount);
+                assert.fail("Trigger with a foreign fee treasury should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidFeeTreasury");
+            }
+
+            // Note: This will fail if not time yet, but demonstrates the flow
+            try {
+                const tx = await triggerWith(paymentEscrow, feeTreasury);
+
                 console.log("✓ Transaction signature:", tx);
 
                 const subscriptionAccount = await program.account.subscription.fetch(subscriptionPda);
@@ -316,18 +435,59 @@ describe("SynapsePay Scheduler Tests", () => {
         it("✅ Should cancel subscription and refund balance", async () => {
             console.log("\n📝 Test: Cancel Subscription");
 
-            const [vaultTokenAccounts] = await provider.connection.getTokenAccountsByOwner(
-                vaultAuthority,
-                { mint: usdcMint }
+            // Refunds may only go to the owner's own token account
+            const stranger = Keypair.generate();
+            const strangerTokenAccount = await createAccount(
+                provider.connection,
+                owner.payer,
+                usdcMint,
+                stranger.publicKey
             );
+            try {
+                await program.methods
+                    .cancelSubscription()
+                    .accounts({
+                        owner: owner.publicKey,
+                        subscription: subscriptionPda,
+                        mint: usdcMint,
+                        ownerTokenAccount: strangerTokenAccount,
+                        subscriptionVault,
+                        vaultAuthority,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+                assert.fail("Refund to a foreign token account should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidTokenOwner");
+            }
+
+            // The vault must be paid out by its own authority
+            try {
+                await program.methods
+                    .cancelSubscription()
+                    .accounts({
+                        owner: owner.publicKey,
+                        subscription: subscriptionPda,
+                        mint: usdcMint,
+                        ownerTokenAccount,
+                        subscriptionVault,
+                        vaultAuthority: owner.publicKey,
+                        tokenProgram: TOKEN_PROGRAM_ID,
+                    })
+                    .rpc();
+                assert.fail("Cancel with a foreign vault authority should fail");
+            } catch (err: any) {
+                assert.include(err.toString(), "InvalidVaultAuthority");
+            }
 
             const tx = await program.methods
                 .cancelSubscription()
                 .accounts({
                     owner: owner.publicKey,
                     subscription: subscriptionPda,
+                    mint: usdcMint,
                     ownerTokenAccount,
-                    subscriptionVault: vaultTokenAccounts.pubkey,
+                    subscriptionVault,
                     vaultAuthority,
                     tokenProgram: TOKEN_PROGRAM_ID,
                 })